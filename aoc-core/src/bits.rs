@@ -0,0 +1,62 @@
+const WORD: usize = u64::BITS as usize;
+
+/// A fixed-size set of small integers, packed a bit each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// An empty set that can hold `0..size`.
+    pub fn new(size: usize) -> Self {
+        BitSet {
+            words: vec![0; size.div_ceil(WORD)],
+        }
+    }
+
+    /// Adds `bit`, returning whether it was not already there.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        let word = &mut self.words[bit / WORD];
+        let mask = 1 << (bit % WORD);
+        let added = *word & mask == 0;
+        *word |= mask;
+        added
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        self.words[bit / WORD] & (1 << (bit % WORD)) != 0
+    }
+
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+
+    /// Empties the set, keeping its size.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_set() {
+        let mut set = BitSet::new(130);
+        assert!(set.insert(3));
+        assert!(set.insert(129));
+        assert!(!set.insert(129));
+        assert!(set.contains(3) && set.contains(129));
+        assert!(!set.contains(64) && !set.contains(0));
+
+        let mut other = BitSet::new(130);
+        other.insert(64);
+        assert!(set.is_disjoint(&other));
+        other.insert(129);
+        assert!(!set.is_disjoint(&other));
+
+        set.clear();
+        assert!(!set.contains(129) && set.is_disjoint(&other));
+    }
+}
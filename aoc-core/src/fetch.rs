@@ -0,0 +1,402 @@
+use crate::input::{cache_file, YEAR};
+use miette::Diagnostic;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// The `session` cookie from a logged-in browser.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// Day 1 unlocks at midnight US Eastern (05:00 UTC) on 2024-12-01.
+const FIRST_UNLOCK_SECS: u64 = 1_733_029_200;
+const DAY_SECS: u64 = 24 * 60 * 60;
+const LAST_DAY: u8 = 25;
+
+/// Records when the last request went out, so separate runs share the limit.
+const STAMP_FILE: &str = ".last-fetch";
+
+/// The only HTTP the fetcher needs, kept behind a trait so tests can serve
+/// inputs from a fixture directory or a local stub server.
+pub trait HttpClient {
+    /// GETs `url` with the session cookie and returns the body of a 200.
+    fn get(&self, url: &str, session: &str) -> Result<String, FetchError>;
+}
+
+/// Serves `<dir>/<url path>`, e.g. `<dir>/2024/day/7/input`.
+#[derive(Debug, Clone)]
+pub struct FixtureClient {
+    dir: PathBuf,
+}
+
+impl FixtureClient {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FixtureClient { dir: dir.into() }
+    }
+}
+
+impl HttpClient for FixtureClient {
+    fn get(&self, url: &str, _session: &str) -> Result<String, FetchError> {
+        let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let path = without_scheme.split_once('/').map_or("", |(_, path)| path);
+        fs::read_to_string(self.dir.join(path)).map_err(|_| FetchError::Status {
+            url: url.to_string(),
+            status: 404,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    MissingSession,
+    NoSuchDay { day: u8 },
+    Locked { day: u8, remaining: Duration },
+    Status { url: String, status: u16 },
+    Transport { url: String, message: String },
+    EmptyBody { url: String },
+    Io { path: PathBuf, error: io::Error },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::MissingSession => write!(f, "no session token found"),
+            FetchError::NoSuchDay { day } => {
+                write!(
+                    f,
+                    "there is no day {}, puzzles run from 1 to {}",
+                    day, LAST_DAY
+                )
+            }
+            FetchError::Locked { day, remaining } => {
+                let secs = remaining.as_secs();
+                write!(
+                    f,
+                    "day {} has not unlocked yet ({}h {:02}m {:02}s to go)",
+                    day,
+                    secs / 3600,
+                    secs / 60 % 60,
+                    secs % 60
+                )
+            }
+            FetchError::Status { url, status } => write!(f, "GET {} returned {}", url, status),
+            FetchError::Transport { url, message } => write!(f, "GET {} failed: {}", url, message),
+            FetchError::EmptyBody { url } => write!(f, "GET {} returned an empty body", url),
+            FetchError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FetchError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Diagnostic for FetchError {
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        match self {
+            FetchError::MissingSession => Some(Box::new(format!(
+                "set {} or write the cookie to ~/.config/aoc/session",
+                SESSION_ENV
+            ))),
+            FetchError::Status { status: 400, .. } => {
+                Some(Box::new("the session token is probably invalid or expired"))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+impl Fetched {
+    pub fn path(&self) -> &Path {
+        match self {
+            Fetched::Cached(path) | Fetched::Downloaded(path) => path,
+        }
+    }
+}
+
+/// When `day`'s puzzle (and input) becomes available, or an error if there is
+/// no such day.
+pub fn unlock_time(day: u8) -> Result<SystemTime, FetchError> {
+    if !(1..=LAST_DAY).contains(&day) {
+        return Err(FetchError::NoSuchDay { day });
+    }
+    let offset = u64::from(day - 1) * DAY_SECS;
+    Ok(UNIX_EPOCH + Duration::from_secs(FIRST_UNLOCK_SECS + offset))
+}
+
+/// `$AOC_SESSION`, else the contents of `$XDG_CONFIG_HOME/aoc/session` (or
+/// `~/.config/aoc/session`).
+pub fn session_from_env() -> Result<String, FetchError> {
+    let non_empty = |key: &str| env::var_os(key).filter(|value| !value.is_empty());
+
+    if let Some(session) = env::var(SESSION_ENV).ok().filter(|s| !s.trim().is_empty()) {
+        return Ok(session.trim().to_string());
+    }
+    let config_dir = non_empty("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .ok_or(FetchError::MissingSession)?;
+    fs::read_to_string(config_dir.join("aoc").join("session"))
+        .ok()
+        .map(|session| session.trim().to_string())
+        .filter(|session| !session.is_empty())
+        .ok_or(FetchError::MissingSession)
+}
+
+/// Downloads inputs into the same cache directory [`crate::InputResolver`]
+/// reads from. A cached input is never fetched twice.
+pub struct Fetcher<C> {
+    client: C,
+    session: String,
+    cache_dir: PathBuf,
+    base_url: String,
+    min_interval: Duration,
+    clock: fn() -> SystemTime,
+    sleep: fn(Duration),
+}
+
+impl<C: HttpClient> Fetcher<C> {
+    pub fn new(client: C, session: impl Into<String>, cache_dir: impl Into<PathBuf>) -> Self {
+        Fetcher {
+            client,
+            session: session.into(),
+            cache_dir: cache_dir.into(),
+            base_url: BASE_URL.to_string(),
+            min_interval: Duration::from_secs(5),
+            clock: SystemTime::now,
+            sleep: thread::sleep,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: fn() -> SystemTime, sleep: fn(Duration)) -> Self {
+        self.clock = clock;
+        self.sleep = sleep;
+        self
+    }
+
+    pub fn cache_path(&self, day: u8) -> PathBuf {
+        cache_file(&self.cache_dir, day)
+    }
+
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, YEAR, day)
+    }
+
+    pub fn fetch(&self, day: u8) -> Result<Fetched, FetchError> {
+        let unlocks = unlock_time(day)?;
+        let path = self.cache_path(day);
+        if path.exists() {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let now = (self.clock)();
+        if let Ok(remaining) = unlocks.duration_since(now) {
+            if !remaining.is_zero() {
+                return Err(FetchError::Locked { day, remaining });
+            }
+        }
+
+        self.throttle(now)?;
+        let url = self.url(day);
+        let body = self.client.get(&url, &self.session)?;
+        if body.trim().is_empty() {
+            return Err(FetchError::EmptyBody { url });
+        }
+
+        write_atomic(&path, &body)?;
+        Ok(Fetched::Downloaded(path))
+    }
+
+    /// Sleeps until `min_interval` has passed since the last recorded request,
+    /// then records this one.
+    fn throttle(&self, now: SystemTime) -> Result<(), FetchError> {
+        let stamp = self.cache_dir.join(STAMP_FILE);
+        let last = fs::read_to_string(&stamp)
+            .ok()
+            .and_then(|millis| millis.trim().parse::<u64>().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+
+        let mut sent_at = now;
+        if let Some(last) = last {
+            let elapsed = now.duration_since(last).unwrap_or_default();
+            if elapsed < self.min_interval {
+                let wait = self.min_interval - elapsed;
+                (self.sleep)(wait);
+                sent_at = now + wait;
+            }
+        }
+
+        let millis = sent_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        write_atomic(&stamp, &millis.to_string())
+    }
+}
+
+fn write_atomic(path: &Path, contents: &str) -> Result<(), FetchError> {
+    let io_error = |error| FetchError::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents).map_err(io_error)?;
+    fs::rename(&tmp, path).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scratch::ScratchDir;
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn december_11_utc() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_733_875_200)
+    }
+
+    struct Counting {
+        calls: Cell<u32>,
+    }
+
+    impl HttpClient for Counting {
+        fn get(&self, url: &str, session: &str) -> Result<String, FetchError> {
+            self.calls.set(self.calls.get() + 1);
+            assert_eq!("secret", session);
+            Ok(format!("body of {}\n", url))
+        }
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        assert_eq!(
+            UNIX_EPOCH + Duration::from_secs(FIRST_UNLOCK_SECS),
+            unlock_time(1).unwrap()
+        );
+        assert_eq!(
+            UNIX_EPOCH + Duration::from_secs(1_734_152_400),
+            unlock_time(14).unwrap()
+        );
+        assert!(unlock_time(25).is_ok());
+        for day in [0, 26, u8::MAX] {
+            assert!(matches!(unlock_time(day), Err(FetchError::NoSuchDay { day: d }) if d == day));
+        }
+    }
+
+    #[test]
+    fn test_fetch_from_fixture_then_cache() {
+        let root = ScratchDir::new("fetch-fixture");
+        let fixtures = root.join("fixtures");
+        fs::create_dir_all(fixtures.join("2024/day/3")).unwrap();
+        fs::write(fixtures.join("2024/day/3/input"), "mul(2,4)\n").unwrap();
+
+        let fetcher = Fetcher::new(FixtureClient::new(&fixtures), "secret", root.join("cache"))
+            .with_min_interval(Duration::ZERO)
+            .with_clock(december_11_utc, |_| {});
+
+        let path = root.join("cache/2024/day-03.txt");
+        assert_eq!(Fetched::Downloaded(path.clone()), fetcher.fetch(3).unwrap());
+        assert_eq!("mul(2,4)\n", fs::read_to_string(&path).unwrap());
+        assert_eq!(Fetched::Cached(path), fetcher.fetch(3).unwrap());
+
+        assert!(matches!(
+            fetcher.fetch(4),
+            Err(FetchError::Status { status: 404, .. })
+        ));
+    }
+
+    #[test]
+    fn test_refuses_locked_days() {
+        let root = ScratchDir::new("fetch-locked");
+        let client = Counting {
+            calls: Cell::new(0),
+        };
+        let fetcher =
+            Fetcher::new(client, "secret", root.path()).with_clock(december_11_utc, |_| {});
+
+        assert!(fetcher.fetch(10).is_ok());
+        let Err(FetchError::Locked { day, remaining }) = fetcher.fetch(11) else {
+            panic!("expected day 11 to be locked")
+        };
+        assert_eq!(11, day);
+        assert_eq!(Duration::from_secs(5 * 60 * 60), remaining);
+        assert_eq!(1, fetcher.client.calls.get());
+    }
+
+    #[test]
+    fn test_refuses_days_out_of_range() {
+        let root = ScratchDir::new("fetch-out-of-range");
+        let client = Counting {
+            calls: Cell::new(0),
+        };
+        let fetcher =
+            Fetcher::new(client, "secret", root.path()).with_clock(december_11_utc, |_| {});
+
+        // Even a cached file for day 0 isn't served.
+        fs::create_dir_all(root.join("2024")).unwrap();
+        fs::write(fetcher.cache_path(0), "stale\n").unwrap();
+        let error = fetcher.fetch(0).unwrap_err();
+        assert_eq!(
+            "there is no day 0, puzzles run from 1 to 25",
+            error.to_string()
+        );
+        assert!(matches!(
+            fetcher.fetch(26),
+            Err(FetchError::NoSuchDay { day: 26 })
+        ));
+        assert_eq!(0, fetcher.client.calls.get());
+        assert!(!root.join(STAMP_FILE).exists());
+    }
+
+    static SLEPT_MS: AtomicU64 = AtomicU64::new(0);
+
+    #[test]
+    fn test_rate_limit() {
+        let root = ScratchDir::new("fetch-rate-limit");
+        let client = Counting {
+            calls: Cell::new(0),
+        };
+        let fetcher = Fetcher::new(client, "secret", root.path())
+            .with_min_interval(Duration::from_secs(5))
+            .with_clock(december_11_utc, |wait| {
+                SLEPT_MS.fetch_add(wait.as_millis() as u64, Ordering::SeqCst);
+            });
+
+        fetcher.fetch(1).unwrap();
+        assert_eq!(0, SLEPT_MS.load(Ordering::SeqCst));
+        fetcher.fetch(2).unwrap();
+        assert_eq!(5_000, SLEPT_MS.load(Ordering::SeqCst));
+        fetcher.fetch(2).unwrap();
+        assert_eq!(5_000, SLEPT_MS.load(Ordering::SeqCst));
+        assert_eq!(2, fetcher.client.calls.get());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::registry::Day;
+use miette::Diagnostic;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub const YEAR: u16 = 2024;
+
+/// Path (or `-`) to read input from. `{day}` and `{year}` are substituted, so
+/// one setting can serve every day, e.g. `~/aoc/inputs/day-{day}.txt`.
+pub const INPUT_ENV: &str = "AOC_INPUT";
+
+/// Overrides the per-user cache directory.
+pub const CACHE_ENV: &str = "AOC_CACHE_DIR";
+
+/// The file checked in next to each day crate's manifest.
+pub const LOCAL_INPUT: &str = "input.txt";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Read {
+        source: InputSource,
+        error: io::Error,
+    },
+    NotFound {
+        day: u8,
+        tried: Vec<PathBuf>,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Read { source, error } => write!(f, "failed to read {}: {}", source, error),
+            InputError::NotFound { day, tried } => {
+                write!(f, "no input found for day {}, tried:", day)?;
+                for path in tried {
+                    write!(f, "\n  {}", path.display())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Read { error, .. } => Some(error),
+            InputError::NotFound { .. } => None,
+        }
+    }
+}
+
+impl Diagnostic for InputError {
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        match self {
+            InputError::Read { .. } => None,
+            InputError::NotFound { .. } => Some(Box::new(format!(
+                "pass --input <PATH> (or `-` for stdin), set {}, or place the file in the cache directory",
+                INPUT_ENV
+            ))),
+        }
+    }
+}
+
+/// Decides where a day's input comes from.
+///
+/// An explicit source is used as-is. Otherwise the candidates are, in order:
+/// the `AOC_INPUT` pattern, the per-user cache, and the day crate's
+/// `input.txt`; the first file that exists wins.
+#[derive(Debug, Clone, Default)]
+pub struct InputResolver {
+    explicit: Option<InputSource>,
+    pattern: Option<String>,
+    cache_dir: Option<PathBuf>,
+}
+
+impl InputResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_env() -> Self {
+        InputResolver {
+            explicit: None,
+            pattern: env::var(INPUT_ENV).ok().filter(|value| !value.is_empty()),
+            cache_dir: default_cache_dir(),
+        }
+    }
+
+    pub fn with_arg(mut self, arg: Option<&str>) -> Self {
+        if let Some(arg) = arg {
+            self.explicit = Some(InputSource::from_arg(arg));
+        }
+        self
+    }
+
+    pub fn with_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    pub fn cache_path(&self, day: u8) -> Option<PathBuf> {
+        self.cache_dir.as_ref().map(|dir| cache_file(dir, day))
+    }
+
+    pub fn candidates(&self, day: &Day) -> Vec<InputSource> {
+        if let Some(source) = &self.explicit {
+            return vec![source.clone()];
+        }
+
+        let mut candidates = vec![];
+        if let Some(pattern) = &self.pattern {
+            let arg = pattern
+                .replace("{day}", &format!("{:02}", day.day))
+                .replace("{year}", &YEAR.to_string());
+            candidates.push(InputSource::from_arg(&arg));
+        }
+        if let Some(path) = self.cache_path(day.day) {
+            candidates.push(InputSource::File(path));
+        }
+        candidates.push(InputSource::File(Path::new(day.dir).join(LOCAL_INPUT)));
+        candidates
+    }
+
+    pub fn read(&self, day: &Day) -> Result<String, InputError> {
+        let mut tried = vec![];
+        for source in self.candidates(day) {
+            let path = match source {
+                InputSource::Stdin => return read_stdin(),
+                InputSource::File(path) => path,
+            };
+            match fs::read_to_string(&path) {
+                Ok(contents) => return Ok(contents),
+                Err(e) if e.kind() == io::ErrorKind::NotFound && self.explicit.is_none() => {
+                    tried.push(path)
+                }
+                Err(error) => {
+                    return Err(InputError::Read {
+                        source: InputSource::File(path),
+                        error,
+                    })
+                }
+            }
+        }
+        Err(InputError::NotFound {
+            day: day.day,
+            tried,
+        })
+    }
+}
+
+/// `$AOC_CACHE_DIR`, else `$XDG_CACHE_HOME/aoc`, else `~/.cache/aoc`.
+pub fn default_cache_dir() -> Option<PathBuf> {
+    let non_empty = |key: &str| env::var_os(key).filter(|value| !value.is_empty());
+
+    if let Some(dir) = non_empty(CACHE_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = non_empty("XDG_CACHE_HOME") {
+        return Some(PathBuf::from(dir).join("aoc"));
+    }
+    non_empty("HOME").map(|home| PathBuf::from(home).join(".cache").join("aoc"))
+}
+
+/// Where a day's input lives inside a cache directory: `<dir>/2024/day-07.txt`.
+pub fn cache_file(dir: &Path, day: u8) -> PathBuf {
+    dir.join(YEAR.to_string())
+        .join(format!("day-{:02}.txt", day))
+}
+
+fn read_stdin() -> Result<String, InputError> {
+    let mut contents = String::new();
+    io::stdin()
+        .read_to_string(&mut contents)
+        .map_err(|error| InputError::Read {
+            source: InputSource::Stdin,
+            error,
+        })?;
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scratch::ScratchDir;
+    use crate::Solution;
+
+    struct Lines;
+
+    impl Solution for Lines {
+        type Input<'a> = usize;
+        type Output = usize;
+
+        fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+            Ok(input.lines().count())
+        }
+
+        fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+            Ok(*input)
+        }
+
+        fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+            Ok(*input)
+        }
+    }
+
+    fn leak(path: &Path) -> &'static str {
+        Box::leak(path.to_str().unwrap().to_string().into_boxed_str())
+    }
+
+    #[test]
+    fn test_resolution_order() {
+        let root = ScratchDir::new("order");
+        let crate_dir = root.join("day-07");
+        let cache_dir = root.join("cache");
+        fs::create_dir_all(&crate_dir).unwrap();
+        fs::write(crate_dir.join(LOCAL_INPUT), "local\n").unwrap();
+        let day = Day::new::<Lines>(7, leak(&crate_dir));
+
+        let resolver = InputResolver::new().with_cache_dir(&cache_dir);
+        assert_eq!("local\n", resolver.read(&day).unwrap());
+
+        fs::create_dir_all(cache_dir.join("2024")).unwrap();
+        fs::write(cache_dir.join("2024/day-07.txt"), "cached\n").unwrap();
+        assert_eq!("cached\n", resolver.read(&day).unwrap());
+
+        fs::write(root.join("env-07-2024.txt"), "from env\n").unwrap();
+        let resolver = resolver.with_pattern(root.join("env-{day}-{year}.txt").to_str().unwrap());
+        assert_eq!("from env\n", resolver.read(&day).unwrap());
+
+        let resolver = resolver.with_arg(Some(root.join("missing.txt").to_str().unwrap()));
+        assert!(matches!(resolver.read(&day), Err(InputError::Read { .. })));
+    }
+
+    #[test]
+    fn test_not_found_names_every_path() {
+        let root = ScratchDir::new("not-found");
+        let day = Day::new::<Lines>(3, leak(&root));
+        let resolver = InputResolver::new()
+            .with_pattern(root.join("day-{day}.txt").to_str().unwrap())
+            .with_cache_dir(root.join("cache"));
+
+        let Err(e) = resolver.read(&day) else {
+            panic!("expected an error")
+        };
+        let message = e.to_string();
+        assert!(message.contains(&root.join("day-03.txt").display().to_string()));
+        assert!(message.contains(&root.join("cache/2024/day-03.txt").display().to_string()));
+        assert!(message.contains(&root.join(LOCAL_INPUT).display().to_string()));
+    }
+
+    #[test]
+    fn test_stdin_arg() {
+        let day = Day::new::<Lines>(1, ".");
+        let resolver = InputResolver::new().with_arg(Some("-"));
+        assert_eq!(vec![InputSource::Stdin], resolver.candidates(&day));
+    }
+}
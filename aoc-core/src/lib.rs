@@ -0,0 +1,15 @@
+pub mod bits;
+pub mod fetch;
+pub mod input;
+pub mod parse;
+pub mod registry;
+#[cfg(test)]
+mod scratch;
+pub mod solution;
+
+pub use bits::BitSet;
+pub use fetch::{FetchError, Fetched, Fetcher, FixtureClient, HttpClient};
+pub use input::{InputError, InputResolver, InputSource};
+pub use parse::{Located, ParseError};
+pub use registry::{run_day, Day, Part, Registry};
+pub use solution::{run_solution, DayRun, PartRun, Solution};
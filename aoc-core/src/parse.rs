@@ -0,0 +1,365 @@
+use miette::{
+    Diagnostic, LabeledSpan, MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents,
+};
+use nom::branch::alt;
+use nom::character::complete::line_ending;
+use nom::combinator::{cut, eof, peek, value};
+use nom::error::{context, ContextError, ErrorKind, FromExternalError};
+use nom::{InputLength, Parser};
+use nom_locate::LocatedSpan;
+use std::fmt;
+
+/// `nom::IResult` with the error type every day's parsers share.
+pub type IResult<I, O> = nom::IResult<I, O, NomError<I>>;
+
+/// What a parser wanted at the point it gave up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    Char(char),
+    Kind(ErrorKind),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            Expected::Char(c) => return write!(f, "{:?}", c),
+            Expected::Kind(kind) => kind,
+        };
+        let description = match kind {
+            ErrorKind::Digit => "a digit",
+            ErrorKind::Alpha => "a letter",
+            ErrorKind::AlphaNumeric => "a letter or digit",
+            ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+            ErrorKind::CrLf => "a line ending",
+            ErrorKind::Eof => "end of input",
+            ErrorKind::Tag => "a literal",
+            kind => kind.description(),
+        };
+        write!(f, "{}", description)
+    }
+}
+
+/// Keeps the failure that got furthest into the input, and the innermost
+/// `context` label wrapped around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NomError<I> {
+    pub input: I,
+    pub expected: Expected,
+    pub context: Option<&'static str>,
+}
+
+impl<I: InputLength> nom::error::ParseError<I> for NomError<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        NomError {
+            input,
+            expected: Expected::Kind(kind),
+            context: None,
+        }
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: I, c: char) -> Self {
+        NomError {
+            input,
+            expected: Expected::Char(c),
+            context: None,
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        if other.input.input_len() < self.input.input_len() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl<I> ContextError<I> for NomError<I> {
+    fn add_context(_input: I, ctx: &'static str, mut other: Self) -> Self {
+        other.context.get_or_insert(ctx);
+        other
+    }
+}
+
+impl<I, E> FromExternalError<I, E> for NomError<I> {
+    fn from_external_error(input: I, kind: ErrorKind, _e: E) -> Self {
+        NomError {
+            input,
+            expected: Expected::Kind(kind),
+            context: None,
+        }
+    }
+}
+
+impl<I> NomError<I> {
+    pub fn expected(&self) -> String {
+        match self.context {
+            Some(context) => context.to_string(),
+            None => self.expected.to_string(),
+        }
+    }
+}
+
+/// Inputs that know where they sit inside the original source.
+pub trait Located {
+    fn offset_in(&self, source: &str) -> usize;
+}
+
+impl Located for &str {
+    fn offset_in(&self, source: &str) -> usize {
+        let offset = (self.as_ptr() as usize).saturating_sub(source.as_ptr() as usize);
+        offset.min(source.len())
+    }
+}
+
+impl Located for LocatedSpan<&str> {
+    fn offset_in(&self, source: &str) -> usize {
+        self.location_offset().min(source.len())
+    }
+}
+
+/// The text a `ParseError` points into. Usually the whole input, but a
+/// streaming parser only has the line it is on, so that line carries the
+/// number it had in the input.
+#[derive(Debug)]
+struct Source {
+    text: String,
+    first_line: usize,
+}
+
+impl SourceCode for Source {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let contents = self
+            .text
+            .read_span(span, context_lines_before, context_lines_after)?;
+        Ok(Box::new(MietteSpanContents::new_named(
+            "input".to_string(),
+            contents.data(),
+            *contents.span(),
+            contents.line() + self.first_line,
+            contents.column(),
+            contents.line_count(),
+        )))
+    }
+}
+
+/// A parse failure pinned to the byte that caused it.
+#[derive(Debug)]
+pub struct ParseError {
+    src: Source,
+    span: SourceSpan,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: Option<char>,
+}
+
+impl ParseError {
+    pub fn new(source: &str, offset: usize, expected: impl Into<String>) -> Self {
+        Self::within(source, 0, offset, expected)
+    }
+
+    /// An error at `offset` into `line`, which is line `line_number` (from 1)
+    /// of an input that is not kept around as a whole.
+    pub fn in_line(
+        line_number: usize,
+        line: &str,
+        offset: usize,
+        expected: impl Into<String>,
+    ) -> Self {
+        Self::within(line, line_number.saturating_sub(1), offset, expected)
+    }
+
+    fn within(source: &str, first_line: usize, offset: usize, expected: impl Into<String>) -> Self {
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        let found = source[offset..].chars().next();
+
+        ParseError {
+            src: Source {
+                text: source.to_string(),
+                first_line,
+            },
+            span: (offset, found.map_or(0, char::len_utf8)).into(),
+            line: first_line + before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected: expected.into(),
+            found,
+        }
+    }
+
+    pub fn from_nom<I: Located>(source: &str, err: nom::Err<NomError<I>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => ParseError::new(source, source.len(), "more input"),
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                ParseError::new(source, e.input.offset_in(source), e.expected())
+            }
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.span.offset()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, found ", self.expected)?;
+        match self.found {
+            None => write!(f, "end of input")?,
+            Some('\n') => write!(f, "end of line")?,
+            Some(c) => write!(f, "{:?}", c)?,
+        }
+        write!(f, " at line {}, column {}", self.line, self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Diagnostic for ParseError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new("aoc::parse"))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.src)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let label = format!("expected {}", self.expected);
+        Some(Box::new(std::iter::once(LabeledSpan::new_with_span(
+            Some(label),
+            self.span,
+        ))))
+    }
+}
+
+/// Runs `parser` over the whole of `source`; anything but trailing whitespace
+/// left over is an error.
+pub fn parse_all<'a, O, P>(source: &'a str, mut parser: P) -> Result<O, ParseError>
+where
+    P: Parser<&'a str, O, NomError<&'a str>>,
+{
+    let (rest, output) = parser
+        .parse(source)
+        .map_err(|e| ParseError::from_nom(source, e))?;
+    let rest = rest.trim_start();
+    if !rest.is_empty() {
+        return Err(ParseError::new(
+            source,
+            rest.offset_in(source),
+            "end of input",
+        ));
+    }
+    Ok(output)
+}
+
+/// Succeeds, without consuming anything, at the end of a line or the input.
+/// Anything else is a hard failure labelled `expected`, so a stray character
+/// is reported where it sits rather than as leftover input.
+pub fn line_end<'a>(expected: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, ()> {
+    cut(context(expected, value((), peek(alt((line_ending, eof))))))
+}
+
+/// Like `separated_list1`, except that once a separator has matched the item
+/// after it must parse too. `1,2,x` then fails at the `x`, not at the comma.
+pub fn list1<'a, O, O2, S, P>(
+    mut separator: S,
+    mut item: P,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    S: Parser<&'a str, O2, NomError<&'a str>>,
+    P: Parser<&'a str, O, NomError<&'a str>>,
+{
+    move |input| {
+        let (mut input, first) = item.parse(input)?;
+        let mut items = vec![first];
+        loop {
+            let next = match separator.parse(input) {
+                Ok((next, _)) => next,
+                Err(nom::Err::Error(_)) => return Ok((input, items)),
+                Err(e) => return Err(e),
+            };
+            let (next, value) = item.parse(next).map_err(|e| match e {
+                nom::Err::Error(e) => nom::Err::Failure(e),
+                e => e,
+            })?;
+            items.push(value);
+            input = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::{digit1, newline, space1};
+    use nom::multi::separated_list1;
+    use nom::sequence::terminated;
+
+    fn rows(input: &str) -> IResult<&str, Vec<Vec<&str>>> {
+        separated_list1(
+            newline,
+            terminated(
+                list1(space1, context("a number", digit1)),
+                line_end("a number or end of line"),
+            ),
+        )(input)
+    }
+
+    #[test]
+    fn test_points_at_stray_character() {
+        let source = "1 2 3\n4 5x 6\n";
+        let e = parse_all(source, rows).unwrap_err();
+        assert_eq!(9, e.offset());
+        assert_eq!((2, 4), (e.line, e.column));
+        assert_eq!(Some('x'), e.found);
+        assert_eq!(
+            "expected a number or end of line, found 'x' at line 2, column 4",
+            e.to_string()
+        );
+
+        let e = parse_all("1 2 x\n", rows).unwrap_err();
+        assert_eq!(
+            "expected a number, found 'x' at line 1, column 5",
+            e.to_string()
+        );
+    }
+
+    #[test]
+    fn test_leftover_input() {
+        let source = "1 2\n\n3";
+        let e = parse_all(source, rows).unwrap_err();
+        assert_eq!((3, 1), (e.line, e.column));
+        assert_eq!("end of input", e.expected);
+        assert_eq!(vec![vec!["1", "2"]], parse_all("1 2\n\n", rows).unwrap());
+    }
+
+    #[test]
+    fn test_in_line_keeps_line_number() {
+        let e = ParseError::in_line(42, "7   x\n", 4, "a number");
+        assert_eq!((42, 5), (e.line, e.column));
+        assert_eq!(Some('x'), e.found);
+
+        let contents = e.source_code().unwrap().read_span(&e.span, 0, 0).unwrap();
+        assert_eq!((41, 4), (contents.line(), contents.column()));
+    }
+
+    #[test]
+    fn test_expected_char() {
+        let e = parse_all("", nom::character::complete::char('|')).unwrap_err();
+        assert_eq!(
+            "expected '|', found end of input at line 1, column 1",
+            e.to_string()
+        );
+    }
+}
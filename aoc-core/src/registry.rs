@@ -0,0 +1,156 @@
+use crate::input::InputResolver;
+use crate::solution::{run_solution, DayRun, Solution};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::process::ExitCode;
+
+pub type RunFn = fn(&str, &[Part]) -> miette::Result<DayRun>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn from_number(number: u8) -> Option<Part> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+/// A single day's entry in the registry.
+///
+/// `dir` is the day crate's manifest directory, where its checked-in
+/// `input.txt` lives.
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub day: u8,
+    pub dir: &'static str,
+    run: RunFn,
+}
+
+impl Day {
+    pub const fn new<S: Solution>(day: u8, dir: &'static str) -> Self {
+        Day {
+            day,
+            dir,
+            run: run_solution::<S>,
+        }
+    }
+
+    pub fn run(&self, resolver: &InputResolver, parts: &[Part]) -> miette::Result<DayRun> {
+        let contents = resolver.read(self)?;
+        self.run_with(&contents, parts)
+    }
+
+    pub fn run_with(&self, contents: &str, parts: &[Part]) -> miette::Result<DayRun> {
+        (self.run)(contents, parts)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    days: BTreeMap<u8, Day>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, day: Day) -> &mut Self {
+        self.days.insert(day.day, day);
+        self
+    }
+
+    pub fn get(&self, day: u8) -> Option<&Day> {
+        self.days.get(&day)
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = &Day> {
+        self.days.values()
+    }
+}
+
+/// Entry point for the per-day binaries. The first argument, if any, is the
+/// input path (`-` for stdin). Answers go to stdout and errors to stderr, and
+/// any error makes the exit status a failure.
+pub fn run_day(day: &Day) -> ExitCode {
+    let arg = std::env::args().nth(1);
+    let resolver = InputResolver::from_env().with_arg(arg.as_deref());
+    let run = match day.run(&resolver, &Part::ALL) {
+        Ok(run) => run,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    for part_run in run.parts {
+        match part_run.answer {
+            Ok(result) => println!("Part {} Result: {}", part_run.part, result),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    impl Solution for Echo {
+        type Input<'a> = &'a str;
+        type Output = String;
+
+        fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+            Ok(input.trim())
+        }
+
+        fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+            Ok(input.to_string())
+        }
+
+        fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+            Ok(input.chars().rev().collect())
+        }
+    }
+
+    #[test]
+    fn test_register() -> miette::Result<()> {
+        let mut registry = Registry::new();
+        registry
+            .register(Day::new::<Echo>(7, "."))
+            .register(Day::new::<Echo>(2, "."));
+
+        let days = registry.days().map(|day| day.day).collect::<Vec<_>>();
+        assert_eq!(vec![2, 7], days);
+
+        let day = registry.get(7).unwrap();
+        let run = day.run_with("abc\n", &[Part::Two])?;
+        assert_eq!(1, run.parts.len());
+        assert_eq!("cba", run.parts[0].answer.as_ref().unwrap());
+        Ok(())
+    }
+}
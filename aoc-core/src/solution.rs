@@ -0,0 +1,104 @@
+use crate::registry::Part;
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// The interface every day implements.
+///
+/// `parse` runs once per input and its result is shared by both parts, so
+/// anything the parts have in common belongs in `Input`.
+pub trait Solution {
+    type Input<'a>;
+    type Output: Display;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>>;
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output>;
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output>;
+
+    fn solve(input: &Self::Input<'_>, part: Part) -> miette::Result<Self::Output> {
+        match part {
+            Part::One => Self::solve_part1(input),
+            Part::Two => Self::solve_part2(input),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: miette::Result<String>,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+pub struct DayRun {
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+/// Parses `input` once, then solves each of `parts` against it.
+pub fn run_solution<S: Solution>(input: &str, parts: &[Part]) -> miette::Result<DayRun> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parse_elapsed = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = S::solve(&parsed, part).map(|output| output.to_string());
+            PartRun {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+
+    Ok(DayRun {
+        parse_elapsed,
+        parts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miette::miette;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input<'a> = Vec<&'a str>;
+        type Output = u32;
+
+        fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+            Ok(input.split_whitespace().collect())
+        }
+
+        fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+            input
+                .iter()
+                .map(|x| x.parse::<u32>().map_err(|e| miette!("{}: {}", x, e)))
+                .sum()
+        }
+
+        fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+            Ok(input.len() as u32)
+        }
+    }
+
+    #[test]
+    fn test_run_solution() -> miette::Result<()> {
+        let run = run_solution::<Sum>("1 2 3", &Part::ALL)?;
+        let answers = run
+            .parts
+            .into_iter()
+            .map(|part_run| part_run.answer)
+            .collect::<miette::Result<Vec<_>>>()?;
+        assert_eq!(vec!["6", "3"], answers);
+
+        let run = run_solution::<Sum>("1 x", &[Part::One])?;
+        assert!(run.parts[0].answer.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+/// A cell position. Rows grow downwards and columns to the right, so `North`
+/// is towards row 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Coord {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Coord { row, col }
+    }
+
+    /// `self` moved by `(d_row, d_col)`, or `None` if either part would go
+    /// negative. Whether the result is inside a grid is the grid's concern.
+    pub fn offset(self, d_row: isize, d_col: isize) -> Option<Coord> {
+        Some(Coord::new(
+            self.row.checked_add_signed(d_row)?,
+            self.col.checked_add_signed(d_col)?,
+        ))
+    }
+
+    pub fn step(self, dir: Direction) -> Option<Coord> {
+        let (d_row, d_col) = dir.delta();
+        self.offset(d_row, d_col)
+    }
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((row, col): (usize, usize)) -> Self {
+        Coord::new(row, col)
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// The 4-connected directions, clockwise from `North`.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The 8-connected directions, clockwise from `North`.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// `(d_row, d_col)` for one step.
+    pub const fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::NorthEast => (-1, 1),
+            Direction::East => (0, 1),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (1, 0),
+            Direction::SouthWest => (1, -1),
+            Direction::West => (0, -1),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    /// A quarter turn clockwise.
+    pub const fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// A quarter turn anticlockwise.
+    pub const fn turn_left(self) -> Direction {
+        self.rotate(6)
+    }
+
+    pub const fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// Rotates clockwise by `eighths` of a full turn.
+    const fn rotate(self, eighths: usize) -> Direction {
+        Direction::ALL[(self as usize + eighths) % 8]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Direction::East, Direction::North.turn_right());
+        assert_eq!(Direction::North, Direction::West.turn_right());
+        assert_eq!(Direction::SouthWest, Direction::NorthWest.turn_left());
+        for dir in Direction::ALL {
+            let (d_row, d_col) = dir.delta();
+            assert_eq!((-d_row, -d_col), dir.opposite().delta());
+        }
+    }
+
+    #[test]
+    fn test_step() {
+        let origin = Coord::new(0, 0);
+        assert_eq!(None, origin.step(Direction::North));
+        assert_eq!(Some(Coord::new(1, 1)), origin.step(Direction::SouthEast));
+        assert_eq!(Some(Coord::new(2, 0)), Coord::new(3, 1).offset(-1, -1));
+    }
+}
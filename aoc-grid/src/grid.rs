@@ -0,0 +1,452 @@
+use crate::coord::{Coord, Direction};
+use aoc_core::ParseError;
+use std::fmt;
+use std::iter;
+use std::ops::{Index, IndexMut};
+
+/// A rectangular grid stored row-major in one flat buffer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// # Panics
+    ///
+    /// If `cells` does not hold exactly `width * height` values.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(width * height, cells.len(), "grid size does not match");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_vec(width, height, vec![value; width * height])
+    }
+
+    /// `None` if the rows are not all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid::from_vec(
+            width,
+            height,
+            rows.into_iter().flatten().collect(),
+        ))
+    }
+
+    /// Builds a grid from lines of text, one cell per char. `cell` returns
+    /// `Err(expected)` for chars it does not accept, and the error points at
+    /// that char. Trailing newlines are ignored; every other line must be as
+    /// wide as the first.
+    pub fn parse<F>(input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, &'static str>,
+    {
+        let body = input.trim_end_matches(['\n', '\r']);
+        if body.is_empty() {
+            return Err(ParseError::new(input, 0, "a grid row"));
+        }
+
+        let mut cells = Vec::with_capacity(body.len());
+        let mut width = None;
+        let mut height = 0;
+        let mut line_start = 0;
+        for line in body.split('\n') {
+            let row = line.strip_suffix('\r').unwrap_or(line);
+            let mut row_width = 0;
+            for (index, c) in row.char_indices() {
+                if width == Some(row_width) {
+                    return Err(ParseError::new(input, line_start + index, "end of line"));
+                }
+                let value = cell(c).map_err(|e| ParseError::new(input, line_start + index, e))?;
+                cells.push(value);
+                row_width += 1;
+            }
+
+            match width {
+                None => width = Some(row_width),
+                Some(width) if row_width < width => {
+                    let expected = format!("{} cells in the row", width);
+                    return Err(ParseError::new(input, line_start + row.len(), expected));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+            line_start += line.len() + 1;
+        }
+
+        Ok(Grid::from_vec(width.unwrap_or(0), height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.row < self.height && coord.col < self.width
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.index_of(coord).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.index_of(coord).map(|index| &mut self.cells[index])
+    }
+
+    /// `coord` moved by `(d_row, d_col)`, if that is still inside the grid.
+    pub fn offset(&self, coord: Coord, d_row: isize, d_col: isize) -> Option<Coord> {
+        coord
+            .offset(d_row, d_col)
+            .filter(|&next| self.contains(next))
+    }
+
+    /// The neighbour of `coord` in direction `dir`, if it is inside the grid.
+    pub fn step(&self, coord: Coord, dir: Direction) -> Option<Coord> {
+        coord.step(dir).filter(|&next| self.contains(next))
+    }
+
+    /// Like `offset`, but leaving one edge re-enters from the opposite one.
+    ///
+    /// # Panics
+    ///
+    /// On an empty grid.
+    pub fn offset_wrapping(&self, coord: Coord, d_row: isize, d_col: isize) -> Coord {
+        let wrap = |value: usize, delta: isize, size: usize| {
+            (value as isize + delta).rem_euclid(size as isize) as usize
+        };
+        Coord::new(
+            wrap(coord.row, d_row, self.height),
+            wrap(coord.col, d_col, self.width),
+        )
+    }
+
+    pub fn step_wrapping(&self, coord: Coord, dir: Direction) -> Coord {
+        let (d_row, d_col) = dir.delta();
+        self.offset_wrapping(coord, d_row, d_col)
+    }
+
+    /// `start` and every cell after it in direction `dir`, up to the edge.
+    pub fn ray(&self, start: Coord, dir: Direction) -> impl Iterator<Item = Coord> + '_ {
+        let start = Some(start).filter(|&start| self.contains(start));
+        iter::successors(start, move |&coord| self.step(coord, dir))
+    }
+
+    pub fn neighbors4(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |dir| self.step(coord, dir))
+    }
+
+    pub fn neighbors8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(coord, dir))
+    }
+
+    /// Every coordinate, row by row.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |index| Coord::new(index / width, index % width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// The first coordinate, row by row, whose value matches.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Coord> {
+        let index = self.cells.iter().position(predicate)?;
+        Some(Coord::new(index / self.width, index % self.width))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` rejects a size of 0, and an empty grid has no rows anyway.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |col| self.column(col))
+    }
+
+    /// Top-left to bottom-right diagonals, starting from the top-right corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.width)
+            .rev()
+            .map(|col| Coord::new(0, col))
+            .chain((1..self.height).map(|row| Coord::new(row, 0)));
+        starts.map(move |start| self.ray(start, Direction::SouthEast).map(|c| &self[c]))
+    }
+
+    /// Top-right to bottom-left diagonals, starting from the top-left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let last_col = self.width.saturating_sub(1);
+        let starts = (0..self.width)
+            .map(|col| Coord::new(0, col))
+            .chain((1..self.height).map(move |row| Coord::new(row, last_col)));
+        starts.map(move |start| self.ray(start, Direction::SouthWest).map(|c| &self[c]))
+    }
+
+    /// Every maximal straight line of cells running in `dir`, each starting
+    /// at the edge the line enters from. `East` gives the rows left to right,
+    /// `West` the same rows right to left, `SouthEast` the main diagonals and
+    /// so on. Each cell is on exactly one line per direction, and lines come
+    /// in the reading order of their first cell.
+    pub fn lines(
+        &self,
+        dir: Direction,
+    ) -> impl Iterator<Item = impl Iterator<Item = Coord> + '_> + '_ {
+        let back = dir.opposite();
+        self.coords()
+            .filter(move |&coord| self.step(coord, back).is_none())
+            .map(move |start| self.ray(start, dir))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// The grid turned a quarter clockwise, so the first column becomes the
+    /// first row, read bottom to top.
+    pub fn rotate_right(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|col| {
+                (0..self.height)
+                    .rev()
+                    .map(move |row| self[Coord::new(row, col)].clone())
+            })
+            .collect();
+        Grid::from_vec(self.height, self.width, cells)
+    }
+
+    fn index_of(&self, coord: Coord) -> Option<usize> {
+        self.contains(coord)
+            .then(|| coord.row * self.width + coord.col)
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        match self.get(coord) {
+            Some(value) => value,
+            None => panic!("{} is outside a {}x{} grid", coord, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(coord) {
+            Some(value) => value,
+            None => panic!("{} is outside a {}x{} grid", coord, width, height),
+        }
+    }
+}
+
+/// One line per row, cells written back to back.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for value in row {
+                write!(f, "{}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn letters() -> Grid<char> {
+        Grid::parse("abc\ndef\n", Ok).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = letters();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!('f', grid[Coord::new(1, 2)]);
+        assert_eq!("abc\ndef", grid.to_string());
+        assert_eq!(Some(Coord::new(1, 1)), grid.position(|&c| c == 'e'));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let digits = |c: char| c.to_digit(10).ok_or("a digit");
+        let e = Grid::parse("12\n3x\n", digits).unwrap_err();
+        assert_eq!((2, 2), (e.line, e.column));
+        assert_eq!("a digit", e.expected);
+
+        let e = Grid::parse("12\n3\n45", digits).unwrap_err();
+        assert_eq!((2, 2), (e.line, e.column));
+        let e = Grid::parse("12\n345\n", digits).unwrap_err();
+        assert_eq!((2, 3), (e.line, e.column));
+        assert!(Grid::parse("\n", digits).is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = letters();
+        let corner = grid.neighbors4(Coord::new(0, 0)).collect::<Vec<_>>();
+        assert_eq!(vec![Coord::new(0, 1), Coord::new(1, 0)], corner);
+        assert_eq!(5, grid.neighbors8(Coord::new(0, 1)).count());
+        assert_eq!(None, grid.step(Coord::new(1, 2), Direction::East));
+        assert_eq!(None, grid.offset(Coord::new(0, 0), 2, 0));
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = letters();
+        let collect = |line: &mut dyn Iterator<Item = &char>| line.collect::<String>();
+
+        let rows = grid
+            .rows()
+            .map(|row| row.iter().collect())
+            .collect::<Vec<String>>();
+        assert_eq!(vec!["abc", "def"], rows);
+        let columns = grid
+            .columns()
+            .map(|mut c| collect(&mut c))
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["ad", "be", "cf"], columns);
+        let diagonals = grid
+            .diagonals()
+            .map(|mut d| collect(&mut d))
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["c", "bf", "ae", "d"], diagonals);
+        let anti = grid
+            .anti_diagonals()
+            .map(|mut d| collect(&mut d))
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["a", "bd", "ce", "f"], anti);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = letters();
+        let ray = grid
+            .ray(Coord::new(0, 0), Direction::East)
+            .collect::<Vec<_>>();
+        assert_eq!(3, ray.len());
+        assert_eq!(0, grid.ray(Coord::new(5, 5), Direction::East).count());
+    }
+
+    #[test]
+    fn test_lines_in_both_directions() {
+        let grid = letters();
+        let spell = |dir| {
+            grid.lines(dir)
+                .map(|line| line.map(|c| grid[c]).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["cba", "fed"], spell(Direction::West));
+        assert_eq!(vec!["da", "eb", "fc"], spell(Direction::North));
+        assert_eq!(vec!["ae", "bf", "c", "d"], spell(Direction::SouthEast));
+        assert_eq!(vec!["a", "db", "ec", "f"], spell(Direction::NorthEast));
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let grid = letters();
+        let corner = Coord::new(0, 0);
+        assert_eq!(
+            Coord::new(1, 2),
+            grid.step_wrapping(corner, Direction::NorthWest)
+        );
+        assert_eq!(Coord::new(0, 1), grid.offset_wrapping(corner, 4, 4));
+    }
+
+    #[test]
+    fn test_rotate_right() {
+        let grid = letters().rotate_right();
+        assert_eq!("da\neb\nfc", grid.to_string());
+        let full_turn = grid.rotate_right().rotate_right().rotate_right();
+        assert_eq!(letters(), full_turn);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_lines_cover_every_cell_once(width in 1..12usize, height in 1..12usize) {
+            let grid = Grid::filled(width, height, ());
+            let mut all = grid.coords().collect::<Vec<_>>();
+            all.sort();
+
+            for dir in Direction::ALL {
+                let lines = grid.lines(dir).map(Iterator::collect).collect::<Vec<Vec<_>>>();
+                for line in &lines {
+                    for pair in line.windows(2) {
+                        prop_assert_eq!(Some(pair[1]), grid.step(pair[0], dir));
+                    }
+                }
+
+                let mut seen = lines.concat();
+                seen.sort();
+                prop_assert_eq!(&all, &seen);
+
+                let mut reversed = grid
+                    .lines(dir.opposite())
+                    .map(|line| line.collect::<Vec<_>>().into_iter().rev().collect())
+                    .collect::<Vec<Vec<_>>>();
+                let mut lines = lines;
+                reversed.sort();
+                lines.sort();
+                prop_assert_eq!(lines, reversed);
+            }
+        }
+
+        #[test]
+        fn prop_value_lines_cover_every_cell_once(width in 1..12usize, height in 1..12usize) {
+            let grid = Grid::from_vec(width, height, (0..width * height).collect());
+            let families = [
+                grid.rows().flatten().copied().collect::<Vec<_>>(),
+                grid.columns().flatten().copied().collect(),
+                grid.diagonals().flatten().copied().collect(),
+                grid.anti_diagonals().flatten().copied().collect(),
+            ];
+            for mut family in families {
+                family.sort();
+                prop_assert_eq!(grid.values().copied().collect::<Vec<_>>(), family);
+            }
+        }
+    }
+}
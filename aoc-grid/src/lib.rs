@@ -0,0 +1,5 @@
+pub mod coord;
+pub mod grid;
+
+pub use coord::{Coord, Direction};
+pub use grid::Grid;
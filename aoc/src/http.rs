@@ -0,0 +1,143 @@
+use aoc_core::{FetchError, HttpClient};
+use std::time::Duration;
+
+const USER_AGENT: &str = "github.com/johnschoeman/advent_of_code_2024";
+
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+impl UreqClient {
+    pub fn new() -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(USER_AGENT)
+            .build();
+        UreqClient { agent }
+    }
+}
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, session: &str) -> Result<String, FetchError> {
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", session))
+            .call();
+        match response {
+            Ok(response) => response.into_string().map_err(|e| FetchError::Transport {
+                url: url.to_string(),
+                message: e.to_string(),
+            }),
+            Err(ureq::Error::Status(status, _)) => Err(FetchError::Status {
+                url: url.to_string(),
+                status,
+            }),
+            Err(e) => Err(FetchError::Transport {
+                url: url.to_string(),
+                message: e.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_core::{Fetched, Fetcher};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Answers a single request with `status` and `body`, returning the request
+    /// head it received.
+    fn stub_server(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut head = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            head
+        });
+        (base_url, handle)
+    }
+
+    /// A cache directory unique to this process and `name`, removed again
+    /// when dropped.
+    struct CacheDir {
+        path: std::path::PathBuf,
+    }
+
+    impl CacheDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("aoc-{}-{}", std::process::id(), name));
+            let _ = std::fs::remove_dir_all(&path);
+            CacheDir { path }
+        }
+    }
+
+    impl Drop for CacheDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn test_fetch_from_stub_server() {
+        let (base_url, server) = stub_server("200 OK", "3   4\n4   3\n");
+        let cache = CacheDir::new("stub-ok");
+        let fetcher = Fetcher::new(UreqClient::new(), "abc123", &cache.path)
+            .with_base_url(base_url)
+            .with_min_interval(Duration::ZERO);
+
+        let fetched = fetcher.fetch(1).unwrap();
+        assert_eq!(
+            Fetched::Downloaded(cache.path.join("2024/day-01.txt")),
+            fetched
+        );
+        assert_eq!(
+            "3   4\n4   3\n",
+            std::fs::read_to_string(fetched.path()).unwrap()
+        );
+
+        let head = server.join().unwrap();
+        assert!(head.starts_with("GET /2024/day/1/input HTTP/1.1"));
+        assert!(head.to_lowercase().contains("cookie: session=abc123"));
+    }
+
+    #[test]
+    fn test_status_error() {
+        let (base_url, server) = stub_server("400 Bad Request", "Please log in");
+        let cache = CacheDir::new("stub-400");
+        let fetcher = Fetcher::new(UreqClient::new(), "expired", &cache.path)
+            .with_base_url(base_url)
+            .with_min_interval(Duration::ZERO);
+
+        assert!(matches!(
+            fetcher.fetch(2),
+            Err(FetchError::Status { status: 400, .. })
+        ));
+        assert!(!cache.path.join("2024/day-02.txt").exists());
+        server.join().unwrap();
+    }
+}
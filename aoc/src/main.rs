@@ -0,0 +1,660 @@
+mod http;
+mod verify;
+
+use aoc_core::fetch::session_from_env;
+use aoc_core::input::default_cache_dir;
+use aoc_core::{Day, Fetched, Fetcher, InputResolver, Part, Registry};
+use aoc_grid::Coord;
+use clap::{Parser, Subcommand, ValueEnum};
+use day_02::analytics;
+use day_02::rules::Rules;
+use day_05::explain;
+use day_06::playback::{self, Playback};
+use day_06::sim::Simulator;
+use http::UreqClient;
+use std::fs::File;
+use std::io::{BufRead, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+use verify::{Answers, Status};
+
+const DEFAULT_ANSWERS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../answers.toml");
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2024 runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one day's solvers, or every registered day with `--all`
+    Run {
+        #[arg(short, long, required_unless_present = "all", conflicts_with = "all")]
+        day: Option<u8>,
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2), requires = "day")]
+        part: Option<u8>,
+        #[arg(long)]
+        all: bool,
+        /// Input file, or `-` for stdin. Defaults to $AOC_INPUT, then the
+        /// cache directory, then the day's `input.txt`
+        #[arg(short, long, value_name = "PATH", conflicts_with = "all")]
+        input: Option<String>,
+    },
+    /// Download puzzle inputs into the cache directory ($AOC_SESSION holds the
+    /// session cookie)
+    Fetch {
+        #[arg(short, long, required_unless_present = "all", conflicts_with = "all")]
+        day: Option<u8>,
+        #[arg(long)]
+        all: bool,
+    },
+    /// Check every part's answer against the answers file
+    Verify {
+        #[arg(short, long)]
+        day: Option<u8>,
+        #[arg(long, value_name = "PATH", default_value = DEFAULT_ANSWERS)]
+        answers: PathBuf,
+        /// Report a part as slow above this multiple of its baseline
+        #[arg(long, default_value_t = 2.0)]
+        tolerance: f64,
+        /// Also fail on missing answers and slow parts
+        #[arg(long)]
+        strict: bool,
+        /// Record the measured time as the baseline for every correct part
+        #[arg(long)]
+        update_baselines: bool,
+    },
+    /// Break down day 2 reports by the first rule they break, with and without
+    /// the Problem Dampener
+    Reports {
+        /// Input file, or `-` for stdin, as for `run`
+        #[arg(short, long, value_name = "PATH")]
+        input: Option<String>,
+        /// Also print every report that is not safe outright
+        #[arg(long)]
+        details: bool,
+        /// Write every classified report to this CSV file
+        #[arg(long, value_name = "PATH")]
+        csv: Option<PathBuf>,
+        /// Write every classified report to this JSON file
+        #[arg(long, value_name = "PATH")]
+        json: Option<PathBuf>,
+    },
+    /// Show which rules each out-of-order day 5 update breaks, and the fewest
+    /// moves that fix it
+    Explain {
+        /// Input file, or `-` for stdin, as for `run`
+        #[arg(short, long, value_name = "PATH")]
+        input: Option<String>,
+        /// Write every explanation to this JSON file
+        #[arg(long, value_name = "PATH")]
+        json: Option<PathBuf>,
+    },
+    /// Play back the day 6 guard's walk a step at a time. While it plays,
+    /// Enter or `p` pauses and resumes, `n` steps, and `q` quits
+    Playback {
+        /// Input file, or `-` for stdin, as for `run`
+        #[arg(short, long, value_name = "PATH")]
+        input: Option<String>,
+        /// Milliseconds between frames
+        #[arg(long, value_name = "MS", default_value_t = 100)]
+        speed: u64,
+        /// Add an obstruction at this row and column, counting from 0
+        #[arg(long, value_name = "ROW,COL", value_parser = parse_coord)]
+        obstruction: Option<Coord>,
+        /// Write frames to this directory instead of playing them
+        #[arg(long, value_name = "DIR")]
+        export: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = FrameFormat::Text, requires = "export")]
+        format: FrameFormat,
+        /// Write every this many frames, and always the last
+        #[arg(long, value_name = "N", default_value_t = 1, requires = "export")]
+        every: usize,
+        /// Pixels to a cell in PPM frames
+        #[arg(long, default_value_t = 8, requires = "export")]
+        scale: usize,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FrameFormat {
+    Text,
+    Ppm,
+}
+
+fn parse_coord(s: &str) -> Result<Coord, String> {
+    let parse = |n: &str| n.trim().parse::<usize>().map_err(|e| e.to_string());
+    match s.split_once(',') {
+        Some((row, col)) => Ok(Coord::new(parse(row)?, parse(col)?)),
+        None => Err("expected ROW,COL".to_string()),
+    }
+}
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    registry
+        .register(day_01::DAY)
+        .register(day_02::DAY)
+        .register(day_03::DAY)
+        .register(day_04::DAY)
+        .register(day_05::DAY)
+        .register(day_06::DAY)
+        .register(day_07::DAY)
+        .register(day_08::DAY)
+        .register(day_09::DAY)
+        .register(day_10::DAY)
+        .register(day_11::DAY);
+    registry
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let registry = registry();
+    let resolver = InputResolver::from_env();
+
+    match cli.command {
+        Command::Run { all: true, .. } => run_all(&registry, &resolver),
+        Command::Run {
+            day, part, input, ..
+        } => {
+            let day = day.expect("clap requires --day without --all");
+            let Some(entry) = registry.get(day) else {
+                eprintln!("Error: day {} is not registered", day);
+                return ExitCode::FAILURE;
+            };
+            let parts = match part.and_then(Part::from_number) {
+                Some(part) => vec![part],
+                None => Part::ALL.to_vec(),
+            };
+            let resolver = resolver.with_arg(input.as_deref());
+            run_parts(entry, &resolver, &parts)
+        }
+        Command::Fetch { day, .. } => {
+            let days = match day {
+                Some(day) => vec![day],
+                None => registry.days().map(|day| day.day).collect(),
+            };
+            fetch(&days)
+        }
+        Command::Verify {
+            day,
+            answers,
+            tolerance,
+            strict,
+            update_baselines,
+        } => {
+            let days = registry
+                .days()
+                .filter(|entry| day.is_none_or(|day| entry.day == day))
+                .collect::<Vec<_>>();
+            if days.is_empty() {
+                eprintln!("Error: day {} is not registered", day.unwrap_or_default());
+                return ExitCode::FAILURE;
+            }
+            let options = VerifyOptions {
+                tolerance,
+                strict,
+                update_baselines,
+            };
+            verify(&days, &resolver, &answers, &options)
+        }
+        Command::Reports {
+            input,
+            details,
+            csv,
+            json,
+        } => {
+            let resolver = resolver.with_arg(input.as_deref());
+            let options = ReportOptions { details, csv, json };
+            reports(&resolver, &options)
+        }
+        Command::Explain { input, json } => {
+            let resolver = resolver.with_arg(input.as_deref());
+            explain(&resolver, json.as_deref())
+        }
+        Command::Playback {
+            input,
+            speed,
+            obstruction,
+            export,
+            format,
+            every,
+            scale,
+        } => {
+            let resolver = resolver.with_arg(input.as_deref());
+            let options = PlaybackOptions {
+                speed: Duration::from_millis(speed),
+                obstruction,
+                export,
+                format,
+                every,
+                scale,
+            };
+            play_back(&resolver, &options)
+        }
+    }
+}
+
+fn run_parts(day: &Day, resolver: &InputResolver, parts: &[Part]) -> ExitCode {
+    let run = match day.run(resolver, parts) {
+        Ok(run) => run,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    for part_run in run.parts {
+        match part_run.answer {
+            Ok(result) => println!("Part {} Result: {}", part_run.part, result),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    status
+}
+
+fn fetch(days: &[u8]) -> ExitCode {
+    let Some(cache_dir) = default_cache_dir() else {
+        eprintln!("Error: no cache directory, set AOC_CACHE_DIR");
+        return ExitCode::FAILURE;
+    };
+    let session = match session_from_env() {
+        Ok(session) => session,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let fetcher = Fetcher::new(UreqClient::new(), session, cache_dir);
+    let mut status = ExitCode::SUCCESS;
+    for &day in days {
+        match fetcher.fetch(day) {
+            Ok(Fetched::Cached(path)) => println!("Day {}: cached at {}", day, path.display()),
+            Ok(Fetched::Downloaded(path)) => {
+                println!("Day {}: downloaded to {}", day, path.display())
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    status
+}
+
+struct VerifyOptions {
+    tolerance: f64,
+    strict: bool,
+    update_baselines: bool,
+}
+
+fn verify(
+    days: &[&Day],
+    resolver: &InputResolver,
+    path: &Path,
+    options: &VerifyOptions,
+) -> ExitCode {
+    let mut answers = match Answers::load(path) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if cfg!(debug_assertions) {
+        eprintln!("note: debug build, timings are not comparable to release baselines");
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    let mut counts = [0; 5];
+    println!(
+        "{:>3}  {:>4}  {:<20}  {:>12}  Status",
+        "Day", "Part", "Answer", "Time"
+    );
+    for day in days {
+        let parts = match day.run(resolver, &Part::ALL) {
+            Ok(run) => run.parts,
+            Err(e) => {
+                println!(
+                    "{:>3}  {:>4}  {:<20}  {:>12}  error: {}",
+                    day.day, "-", "", "-", e
+                );
+                status = ExitCode::FAILURE;
+                counts[3] += Part::ALL.len();
+                continue;
+            }
+        };
+
+        for part_run in parts {
+            let expected = answers.get(day.day, part_run.part);
+            let result = verify::check(
+                expected,
+                &part_run.answer,
+                part_run.elapsed,
+                options.tolerance,
+            );
+            if result.is_failure(options.strict) {
+                status = ExitCode::FAILURE;
+            }
+            if options.update_baselines && matches!(result, Status::Ok | Status::Slow { .. }) {
+                answers.set_baseline(day.day, part_run.part, part_run.elapsed);
+            }
+
+            let index = match result {
+                Status::Ok => 0,
+                Status::Mismatch { .. } => 1,
+                Status::Missing => 2,
+                Status::Failed(_) => 3,
+                Status::Slow { .. } => 4,
+            };
+            counts[index] += 1;
+            println!(
+                "{:>3}  {:>4}  {:<20}  {:>12}  {}",
+                day.day,
+                part_run.part,
+                part_run.answer.as_deref().unwrap_or("-"),
+                format!("{:.2?}", part_run.elapsed),
+                result
+            );
+        }
+    }
+    println!(
+        "{} ok, {} mismatched, {} missing, {} failed, {} slow",
+        counts[0], counts[1], counts[2], counts[3], counts[4]
+    );
+
+    if options.update_baselines {
+        if let Err(e) = answers.save(path) {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    status
+}
+
+struct ReportOptions {
+    details: bool,
+    csv: Option<PathBuf>,
+    json: Option<PathBuf>,
+}
+
+fn reports(resolver: &InputResolver, options: &ReportOptions) -> ExitCode {
+    let reports = match resolver
+        .read(&day_02::DAY)
+        .map_err(miette::Report::from)
+        .and_then(|contents| day_02::parse(&contents))
+    {
+        Ok(reports) => reports,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let rules = Rules::default().with_max_bad_levels(1);
+    let analyses = analytics::analyze(&reports, &rules);
+
+    let count = |status| analyses.iter().filter(|a| a.status == status).count();
+    println!(
+        "{} reports: {} safe, {} dampened, {} unsafe",
+        analyses.len(),
+        count(analytics::Status::Safe),
+        count(analytics::Status::Dampened),
+        count(analytics::Status::Unsafe)
+    );
+    println!();
+    println!("{:<18}  {:>8}  {:>6}", "Reason", "Dampened", "Unsafe");
+    let histogram = analytics::histogram(&analyses);
+    let widest = histogram
+        .values()
+        .map(|c| c.dampened + c.still_unsafe)
+        .max()
+        .unwrap_or(0);
+    for (reason, counts) in &histogram {
+        let total = counts.dampened + counts.still_unsafe;
+        println!(
+            "{:<18}  {:>8}  {:>6}  {}",
+            reason,
+            counts.dampened,
+            counts.still_unsafe,
+            "#".repeat((total * 40).div_ceil(widest.max(1)))
+        );
+    }
+
+    if options.details {
+        println!();
+        for analysis in analyses
+            .iter()
+            .filter(|a| a.status != analytics::Status::Safe)
+        {
+            let removed = match analysis.removed.as_slice() {
+                [] => String::new(),
+                [level] => format!(", drop level {}", level),
+                levels => format!(", drop levels {:?}", levels),
+            };
+            println!(
+                "report {:>4}  {:<8}  {}{}",
+                analysis.report,
+                analysis.status,
+                analysis.message.as_deref().unwrap_or_default(),
+                removed
+            );
+        }
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    if let Some(path) = &options.csv {
+        if let Err(e) = export(path, |file| analytics::write_csv(file, &analyses)) {
+            eprintln!("Error: {}", e);
+            status = ExitCode::FAILURE;
+        }
+    }
+    if let Some(path) = &options.json {
+        if let Err(e) = export(path, |file| analytics::write_json(file, &analyses)) {
+            eprintln!("Error: {}", e);
+            status = ExitCode::FAILURE;
+        }
+    }
+    status
+}
+
+fn explain(resolver: &InputResolver, json: Option<&Path>) -> ExitCode {
+    let explained = resolver
+        .read(&day_05::DAY)
+        .map_err(miette::Report::from)
+        .and_then(|contents| day_05::parse(&contents))
+        .and_then(|(rules, updates)| {
+            let explanations = explain::explain(&rules, &updates)?;
+            Ok((updates.len(), explanations))
+        });
+    let (updates, explanations) = match explained {
+        Ok(explained) => explained,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let moves = explanations.iter().map(|e| e.moves.len()).sum::<usize>();
+    println!(
+        "{} of {} updates out of order, fixed with {} moves",
+        explanations.len(),
+        updates,
+        moves
+    );
+    for explanation in &explanations {
+        println!();
+        println!("{}", explanation);
+    }
+
+    if let Some(path) = json {
+        if let Err(e) = export(path, |file| explain::write_json(file, &explanations)) {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+struct PlaybackOptions {
+    speed: Duration,
+    obstruction: Option<Coord>,
+    export: Option<PathBuf>,
+    format: FrameFormat,
+    every: usize,
+    scale: usize,
+}
+
+fn play_back(resolver: &InputResolver, options: &PlaybackOptions) -> ExitCode {
+    let lab = match resolver
+        .read(&day_06::DAY)
+        .map_err(miette::Report::from)
+        .and_then(|contents| day_06::parse(&contents))
+    {
+        Ok(lab) => lab,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(simulator) = Simulator::new(&lab) else {
+        eprintln!("Error: there is no guard in the lab");
+        return ExitCode::FAILURE;
+    };
+    let playback = match Playback::new(&simulator, options.obstruction) {
+        Ok(playback) => playback,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(dir) = &options.export else {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if let Some(command) = playback::Command::parse(&line) {
+                    if sender.send(command).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        if let Err(e) = playback::play(&playback, options.speed, &receiver, std::io::stdout()) {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    };
+
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        eprintln!("Error: failed to create {}: {}", dir.display(), e);
+        return ExitCode::FAILURE;
+    }
+    let last = playback.len().saturating_sub(1);
+    let indexes = (0..last).step_by(options.every.max(1)).chain([last]);
+    let mut written = 0;
+    for index in indexes {
+        let frame = playback.frame(index);
+        let result = match options.format {
+            FrameFormat::Text => {
+                export(&dir.join(format!("frame-{:05}.txt", index)), |mut file| {
+                    std::io::Write::write_all(&mut file, frame.to_string().as_bytes())
+                })
+            }
+            FrameFormat::Ppm => export(&dir.join(format!("frame-{:05}.ppm", index)), |file| {
+                frame.write_ppm(file, options.scale)
+            }),
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+        written += 1;
+    }
+    println!(
+        "wrote {} frames to {}, {}",
+        written,
+        dir.display(),
+        playback.frame(last).status()
+    );
+    ExitCode::SUCCESS
+}
+
+fn export(
+    path: &Path,
+    write: impl FnOnce(BufWriter<File>) -> std::io::Result<()>,
+) -> miette::Result<()> {
+    File::create(path)
+        .and_then(|file| write(BufWriter::new(file)))
+        .map_err(|e| miette::miette!("failed to write {}: {}", path.display(), e))
+}
+
+fn run_all(registry: &Registry, resolver: &InputResolver) -> ExitCode {
+    let mut status = ExitCode::SUCCESS;
+    let mut total = Duration::ZERO;
+
+    println!(
+        "{:>3}  {:>5}  {:<20}  {:>12}",
+        "Day", "Part", "Answer", "Time"
+    );
+    for day in registry.days() {
+        let run = match day.run(resolver, &Part::ALL) {
+            Ok(run) => run,
+            Err(e) => {
+                status = ExitCode::FAILURE;
+                println!(
+                    "{:>3}  {:>5}  {:<20}  {:>12}",
+                    day.day,
+                    "-",
+                    format!("error: {}", e),
+                    "-"
+                );
+                continue;
+            }
+        };
+
+        total += run.parse_elapsed;
+        print_row(day.day, "parse", "", run.parse_elapsed);
+        for part_run in run.parts {
+            total += part_run.elapsed;
+            let answer = part_run.answer.unwrap_or_else(|e| {
+                status = ExitCode::FAILURE;
+                format!("error: {}", e)
+            });
+            print_row(
+                day.day,
+                &part_run.part.to_string(),
+                &answer,
+                part_run.elapsed,
+            );
+        }
+    }
+    println!(
+        "{:>3}  {:>5}  {:<20}  {:>12}",
+        "",
+        "",
+        "Total",
+        format!("{:.2?}", total)
+    );
+
+    status
+}
+
+fn print_row(day: u8, part: &str, answer: &str, elapsed: Duration) {
+    println!(
+        "{:>3}  {:>5}  {:<20}  {:>12}",
+        day,
+        part,
+        answer,
+        format!("{:.2?}", elapsed)
+    );
+}
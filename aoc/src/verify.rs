@@ -0,0 +1,222 @@
+use aoc_core::Part;
+use miette::{miette, IntoDiagnostic};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Differences smaller than this are noise, whatever the ratio.
+const MIN_SLACK: Duration = Duration::from_millis(1);
+
+/// Known-good answers, checked in as `answers.toml`:
+///
+/// ```toml
+/// [day-07.part1]
+/// answer = "10741443549536"
+/// baseline_ms = 4.2
+/// ```
+///
+/// Answers are strings so every `Output` type compares the same way.
+/// Baselines are optional and only meaningful for `--release` runs.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Answers {
+    days: BTreeMap<String, DayAnswers>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DayAnswers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part1: Option<Expected>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part2: Option<Expected>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Expected {
+    pub answer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_ms: Option<f64>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> miette::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| miette!("failed to read {}: {}", path.display(), e))?;
+        Self::parse(&contents)
+            .map_err(|e| miette!("invalid answers file {}: {}", path.display(), e))
+    }
+
+    pub fn parse(contents: &str) -> miette::Result<Self> {
+        let answers: Answers = toml::from_str(contents).into_diagnostic()?;
+        if let Some(key) = answers.days.keys().find(|key| day_from_key(key).is_none()) {
+            return Err(miette!("unexpected table [{}], expected [day-NN]", key));
+        }
+        Ok(answers)
+    }
+
+    pub fn save(&self, path: &Path) -> miette::Result<()> {
+        let contents = toml::to_string(self).into_diagnostic()?;
+        fs::write(path, contents).map_err(|e| miette!("failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Expected> {
+        let answers = self.days.get(&day_key(day))?;
+        match part {
+            Part::One => answers.part1.as_ref(),
+            Part::Two => answers.part2.as_ref(),
+        }
+    }
+
+    pub fn set_baseline(&mut self, day: u8, part: Part, elapsed: Duration) {
+        let Some(answers) = self.days.get_mut(&day_key(day)) else {
+            return;
+        };
+        let expected = match part {
+            Part::One => answers.part1.as_mut(),
+            Part::Two => answers.part2.as_mut(),
+        };
+        if let Some(expected) = expected {
+            // Two decimals is plenty and keeps the diff readable.
+            expected.baseline_ms = Some((elapsed.as_secs_f64() * 100_000.0).round() / 100.0);
+        }
+    }
+}
+
+fn day_key(day: u8) -> String {
+    format!("day-{:02}", day)
+}
+
+fn day_from_key(key: &str) -> Option<u8> {
+    key.strip_prefix("day-")?.parse().ok()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Ok,
+    Mismatch { expected: String },
+    Missing,
+    Failed(String),
+    Slow { baseline: Duration },
+}
+
+impl Status {
+    /// Whether this status fails `verify`; missing answers and slow parts only
+    /// count under `--strict`.
+    pub fn is_failure(&self, strict: bool) -> bool {
+        match self {
+            Status::Ok => false,
+            Status::Mismatch { .. } | Status::Failed(_) => true,
+            Status::Missing | Status::Slow { .. } => strict,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Mismatch { expected } => write!(f, "MISMATCH, expected {}", expected),
+            Status::Missing => write!(f, "no recorded answer"),
+            Status::Failed(e) => write!(f, "error: {}", e),
+            Status::Slow { baseline } => write!(f, "slow, baseline {:.2?}", baseline),
+        }
+    }
+}
+
+pub fn check(
+    expected: Option<&Expected>,
+    answer: &miette::Result<String>,
+    elapsed: Duration,
+    tolerance: f64,
+) -> Status {
+    let actual = match answer {
+        Ok(actual) => actual,
+        Err(e) => return Status::Failed(e.to_string()),
+    };
+    let Some(expected) = expected else {
+        return Status::Missing;
+    };
+    if *actual != expected.answer {
+        return Status::Mismatch {
+            expected: expected.answer.clone(),
+        };
+    }
+
+    if let Some(baseline_ms) = expected.baseline_ms {
+        let baseline = Duration::from_secs_f64(baseline_ms / 1000.0);
+        if elapsed.as_secs_f64() > baseline.as_secs_f64() * tolerance
+            && elapsed.saturating_sub(baseline) > MIN_SLACK
+        {
+            return Status::Slow { baseline };
+        }
+    }
+    Status::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = r#"
+[day-02]
+part1 = { answer = "294", baseline_ms = 10.0 }
+part2 = { answer = "349" }
+
+[day-11]
+part2 = { answer = "277444936413293" }
+"#;
+
+    #[test]
+    fn test_parse() -> miette::Result<()> {
+        let answers = Answers::parse(ANSWERS)?;
+        assert_eq!("294", answers.get(2, Part::One).unwrap().answer);
+        assert_eq!(Some(10.0), answers.get(2, Part::One).unwrap().baseline_ms);
+        assert_eq!(None, answers.get(2, Part::Two).unwrap().baseline_ms);
+        assert!(answers.get(11, Part::One).is_none());
+        assert!(answers.get(5, Part::One).is_none());
+
+        assert!(Answers::parse("[two]\npart1 = { answer = \"1\" }").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check() -> miette::Result<()> {
+        let answers = Answers::parse(ANSWERS)?;
+        let expected = answers.get(2, Part::One);
+        let ms = Duration::from_millis;
+
+        assert_eq!(Status::Ok, check(expected, &Ok("294".into()), ms(12), 2.0));
+        assert_eq!(
+            Status::Mismatch {
+                expected: "294".into()
+            },
+            check(expected, &Ok("295".into()), ms(1), 2.0)
+        );
+        assert_eq!(
+            Status::Slow { baseline: ms(10) },
+            check(expected, &Ok("294".into()), ms(25), 2.0)
+        );
+        assert_eq!(Status::Ok, check(expected, &Ok("294".into()), ms(25), 3.0));
+        assert_eq!(Status::Missing, check(None, &Ok("1".into()), ms(1), 2.0));
+        assert!(matches!(
+            check(expected, &Err(miette!("boom")), ms(1), 2.0),
+            Status::Failed(_)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_baseline_round_trip() -> miette::Result<()> {
+        let mut answers = Answers::parse(ANSWERS)?;
+        answers.set_baseline(2, Part::Two, Duration::from_micros(1234));
+        answers.set_baseline(5, Part::One, Duration::from_millis(1));
+
+        let answers = Answers::parse(&toml::to_string(&answers).into_diagnostic()?)?;
+        assert_eq!(Some(1.23), answers.get(2, Part::Two).unwrap().baseline_ms);
+        assert!(answers.get(5, Part::One).is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+pub mod locations;
+
+use aoc_core::{Day, Solution};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input<'a> = locations::LocationLists;
+    type Output = i64;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        locations::read_lists(input.as_bytes())
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(locations::total_distance(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(locations::similarity_score(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day01>(1, env!("CARGO_MANIFEST_DIR"));
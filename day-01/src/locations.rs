@@ -0,0 +1,163 @@
+use aoc_core::ParseError;
+use miette::miette;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+pub type LocationId = i32;
+pub type LocationLists = (Vec<LocationId>, Vec<LocationId>);
+
+const LOCATION_ID: &str = "a location id";
+
+/// Reads `left right` pairs a line at a time, reusing one line buffer, so the
+/// only allocations are the two lists themselves. Trailing blank lines are
+/// fine; anything after them is not.
+pub fn read_lists<R: BufRead>(mut reader: R) -> miette::Result<LocationLists> {
+    let mut lists: LocationLists = (Vec::new(), Vec::new());
+    let mut line = String::new();
+    let mut line_number = 0;
+    let mut blank_line = None;
+
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| miette!("failed to read line {}: {}", line_number + 1, e))?;
+        if read == 0 {
+            break;
+        }
+        line_number += 1;
+
+        if line.trim().is_empty() {
+            blank_line.get_or_insert(line_number);
+            continue;
+        }
+        if blank_line.is_some() {
+            let start = line.len() - line.trim_start().len();
+            return Err(ParseError::in_line(line_number, &line, start, "end of input").into());
+        }
+
+        let (left, right) = parse_pair(&line).map_err(|(offset, expected)| {
+            ParseError::in_line(line_number, &line, offset, expected)
+        })?;
+        lists.0.push(left);
+        lists.1.push(right);
+    }
+
+    if lists.0.is_empty() {
+        return Err(ParseError::new("", 0, LOCATION_ID).into());
+    }
+    Ok(lists)
+}
+
+/// Parses `<id> <whitespace> <id>`, or says where and what it expected.
+fn parse_pair(line: &str) -> Result<(LocationId, LocationId), (usize, &'static str)> {
+    let (left, end) = location_id(line, 0)?;
+    let gap = line[end..].len() - line[end..].trim_start_matches([' ', '\t']).len();
+    if gap == 0 {
+        return Err((end, "whitespace between the location ids"));
+    }
+    let (right, end) = location_id(line, end + gap)?;
+    if !line[end..].trim().is_empty() {
+        let stray = end + line[end..].len() - line[end..].trim_start().len();
+        return Err((stray, "end of line after two location ids"));
+    }
+    Ok((left, right))
+}
+
+fn location_id(line: &str, start: usize) -> Result<(LocationId, usize), (usize, &'static str)> {
+    let rest = &line[start..];
+    let sign = usize::from(rest.starts_with(['-', '+']));
+    let digits = rest[sign..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len() - sign);
+    if digits == 0 {
+        return Err((start, LOCATION_ID));
+    }
+    let end = start + sign + digits;
+    let id = line[start..end]
+        .parse()
+        .map_err(|_| (start, "a location id that fits in 32 bits"))?;
+    Ok((id, end))
+}
+
+/// Part 1: pair the lists up smallest to smallest and add the distances.
+pub fn total_distance(lists: &LocationLists) -> i64 {
+    let (mut left, mut right) = lists.clone();
+    left.sort_unstable();
+    right.sort_unstable();
+
+    left.iter()
+        .zip(&right)
+        .map(|(a, b)| (i64::from(*a) - i64::from(*b)).abs())
+        .sum()
+}
+
+/// Part 2: each left id times the number of times it appears on the right.
+pub fn similarity_score(lists: &LocationLists) -> i64 {
+    let (left, right) = lists;
+
+    let mut counts: HashMap<LocationId, i64> = HashMap::with_capacity(right.len());
+    for id in right {
+        *counts.entry(*id).or_default() += 1;
+    }
+
+    left.iter()
+        .map(|id| i64::from(*id) * counts.get(id).copied().unwrap_or(0))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+
+    #[test]
+    fn test_total_distance() -> miette::Result<()> {
+        assert_eq!(11, total_distance(&read_lists(EXAMPLE.as_bytes())?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_similarity_score() -> miette::Result<()> {
+        assert_eq!(31, similarity_score(&read_lists(EXAMPLE.as_bytes())?));
+
+        let contents = "\
+1 2
+5 4
+3 1
+1 3
+";
+        assert_eq!(5, similarity_score(&read_lists(contents.as_bytes())?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |contents: &str| {
+            let e = read_lists(contents.as_bytes()).unwrap_err();
+            let e = e.downcast_ref::<ParseError>().unwrap();
+            (e.line, e.column, e.expected.clone())
+        };
+
+        assert_eq!((2, 5, LOCATION_ID.into()), error("1   2\n3   x\n"));
+        assert_eq!(
+            (1, 7, "end of line after two location ids".into()),
+            error("1   2 3\n")
+        );
+        assert_eq!((3, 1, "end of input".into()), error("1   2\n\n3   4\n"));
+        assert_eq!((1, 1, LOCATION_ID.into()), error("\n"));
+        assert_eq!(
+            (1, 1, "a location id that fits in 32 bits".into()),
+            error("99999999999 1\n")
+        );
+        assert!(read_lists("1   2\r\n3   4\r\n\n".as_bytes()).is_ok());
+    }
+}
@@ -1,9 +1,5 @@
-mod day_1;
+use std::process::ExitCode;
 
-fn main() {
-    println!("Day 1");
-    match day_1::run() {
-        Ok(result) => println!("Result: {}", result),
-        Err(e) => println!("Error: {}", e),
-    }
+fn main() -> ExitCode {
+    aoc_core::run_day(&day_01::DAY)
 }
@@ -0,0 +1,240 @@
+use crate::rules::{Failure, Rules, Verdict};
+use crate::Report;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Whether a report passes the rules outright, only once levels are dropped,
+/// or not at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Safe,
+    Dampened,
+    Unsafe,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Safe => f.pad("safe"),
+            Status::Dampened => f.pad("dampened"),
+            Status::Unsafe => f.pad("unsafe"),
+        }
+    }
+}
+
+/// The kind of the first rule a report breaks, for grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+    DirectionSwitch,
+    ZeroDelta,
+    SmallStep,
+    LargeJump,
+}
+
+impl From<Failure> for Reason {
+    fn from(failure: Failure) -> Self {
+        match failure {
+            Failure::Switched { .. } => Reason::DirectionSwitch,
+            Failure::StepTooSmall { step: 0 } => Reason::ZeroDelta,
+            Failure::StepTooSmall { .. } => Reason::SmallStep,
+            Failure::StepTooLarge { .. } => Reason::LargeJump,
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::DirectionSwitch => f.pad("direction switch"),
+            Reason::ZeroDelta => f.pad("zero delta"),
+            Reason::SmallStep => f.pad("small step"),
+            Reason::LargeJump => f.pad("large jump"),
+        }
+    }
+}
+
+/// One report, classified. `reason`, `index` and `message` describe the first
+/// rule it breaks without the dampener; `removed` is what the dampener drops.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportAnalysis {
+    /// 1-based, matching the line in the input.
+    pub report: usize,
+    pub status: Status,
+    pub reason: Option<Reason>,
+    pub index: Option<usize>,
+    pub message: Option<String>,
+    pub removed: Vec<usize>,
+    pub levels: Report,
+}
+
+pub fn analyze(reports: &[Report], rules: &Rules) -> Vec<ReportAnalysis> {
+    reports
+        .iter()
+        .enumerate()
+        .map(|(index, levels)| {
+            let verdict = rules.check(levels);
+            let violation = verdict.violation();
+            let (status, removed) = match verdict {
+                Verdict::Safe => (Status::Safe, Vec::new()),
+                Verdict::Tolerated { removed, .. } => (Status::Dampened, removed),
+                Verdict::Unsafe(_) => (Status::Unsafe, Vec::new()),
+            };
+            ReportAnalysis {
+                report: index + 1,
+                status,
+                reason: violation.map(|v| v.failure.into()),
+                index: violation.map(|v| v.index),
+                message: violation.map(|v| v.to_string()),
+                removed,
+                levels: levels.clone(),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReasonCount {
+    pub dampened: usize,
+    pub still_unsafe: usize,
+}
+
+/// How often each reason turns up, split by whether the dampener rescued the
+/// report. Reports that were never in trouble are not counted.
+pub fn histogram(analyses: &[ReportAnalysis]) -> BTreeMap<Reason, ReasonCount> {
+    let mut histogram = BTreeMap::<Reason, ReasonCount>::new();
+    for analysis in analyses {
+        let Some(reason) = analysis.reason else {
+            continue;
+        };
+        let count = histogram.entry(reason).or_default();
+        match analysis.status {
+            Status::Dampened => count.dampened += 1,
+            Status::Unsafe => count.still_unsafe += 1,
+            Status::Safe => {}
+        }
+    }
+    histogram
+}
+
+pub fn write_csv<W: Write>(mut writer: W, analyses: &[ReportAnalysis]) -> io::Result<()> {
+    writeln!(writer, "report,status,reason,index,message,removed,levels")?;
+    for analysis in analyses {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{}",
+            analysis.report,
+            analysis.status,
+            analysis.reason.map(|r| r.to_string()).unwrap_or_default(),
+            analysis.index.map(|i| i.to_string()).unwrap_or_default(),
+            csv_field(analysis.message.as_deref().unwrap_or_default()),
+            join(&analysis.removed),
+            join(&analysis.levels),
+        )?;
+    }
+    Ok(())
+}
+
+pub fn write_json<W: Write>(writer: W, analyses: &[ReportAnalysis]) -> io::Result<()> {
+    serde_json::to_writer_pretty(writer, analyses).map_err(io::Error::from)
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    const EXAMPLE: &str = "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
+    fn example() -> miette::Result<Vec<ReportAnalysis>> {
+        let rules = Rules::default().with_max_bad_levels(1);
+        Ok(analyze(&parse(EXAMPLE)?, &rules))
+    }
+
+    #[test]
+    fn test_analyze() -> miette::Result<()> {
+        let analyses = example()?;
+        let statuses = analyses.iter().map(|a| a.status).collect::<Vec<_>>();
+        let (safe, dampened, not_safe) = (Status::Safe, Status::Dampened, Status::Unsafe);
+        assert_eq!(
+            vec![safe, not_safe, not_safe, dampened, dampened, safe],
+            statuses
+        );
+
+        assert_eq!(Some(Reason::ZeroDelta), analyses[4].reason);
+        assert_eq!(Some(2), analyses[4].index);
+        assert_eq!(vec![2], analyses[4].removed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_histogram() -> miette::Result<()> {
+        let histogram = histogram(&example()?);
+        let count = |dampened, still_unsafe| ReasonCount {
+            dampened,
+            still_unsafe,
+        };
+        assert_eq!(
+            vec![
+                (Reason::DirectionSwitch, count(1, 0)),
+                (Reason::ZeroDelta, count(1, 0)),
+                (Reason::LargeJump, count(0, 2)),
+            ],
+            histogram.into_iter().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_csv() -> miette::Result<()> {
+        let mut out = Vec::new();
+        write_csv(&mut out, &example()?[..2]).unwrap();
+        assert_eq!(
+            "\
+report,status,reason,index,message,removed,levels
+1,safe,,,,,7 6 4 2 1
+2,unsafe,large jump,1,levels 1 and 2 (2 7): diff value is 5,,1 2 7 8 9
+",
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!("\"a, \"\"b\"\"\"", csv_field("a, \"b\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_json() -> miette::Result<()> {
+        let mut out = Vec::new();
+        write_json(&mut out, &example()?[3..4]).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!("dampened", json[0]["status"]);
+        assert_eq!("direction_switch", json[0]["reason"]);
+        assert_eq!(serde_json::json!([1]), json[0]["removed"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+pub mod analytics;
+pub mod part_1;
+pub mod part_2;
+pub mod rules;
+
+use aoc_core::parse::{line_end, list1, parse_all, IResult};
+use aoc_core::{Day, Solution};
+use nom::{
+    character::complete::{self, newline, space1},
+    error::context,
+    multi::separated_list1,
+    sequence::terminated,
+};
+
+pub type Report = Vec<i32>;
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input<'a> = Vec<Report>;
+    type Output = usize;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        part_1::process(input)
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_2::count_safe_reports(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day02>(2, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<Vec<Report>> {
+    Ok(parse_all(input, parse_reports)?)
+}
+
+fn parse_reports(input: &str) -> IResult<&str, Vec<Report>> {
+    separated_list1(
+        newline,
+        terminated(
+            list1(space1, context("a level", complete::i32)),
+            line_end("a level or end of line"),
+        ),
+    )(input)
+}
@@ -0,0 +1,434 @@
+use itertools::Itertools;
+use std::cmp::{Ordering, Reverse};
+use std::fmt;
+use std::ops::RangeInclusive;
+use tracing::instrument;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Increasing => write!(f, "increasing"),
+            Direction::Decreasing => write!(f, "decreasing"),
+        }
+    }
+}
+
+/// Which way the levels of a report have to go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Monotonic {
+    /// All one way, whichever way the report starts.
+    Either,
+    Only(Direction),
+    /// No constraint; only the step sizes are checked.
+    Unconstrained,
+}
+
+/// How a pair of neighbouring levels broke the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Failure {
+    /// The levels went the other way from `expected`.
+    Switched {
+        expected: Direction,
+    },
+    StepTooSmall {
+        step: u32,
+    },
+    StepTooLarge {
+        step: u32,
+    },
+}
+
+/// The first rule a report broke. `index` is the position of the first of
+/// the two levels involved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    pub index: usize,
+    pub levels: (i32, i32),
+    pub failure: Failure,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a, b) = self.levels;
+        write!(
+            f,
+            "levels {} and {} ({} {}): ",
+            self.index,
+            self.index + 1,
+            a,
+            b
+        )?;
+        match self.failure {
+            Failure::Switched { expected } => {
+                write!(f, "switched from {}", expected)
+            }
+            Failure::StepTooSmall { step } | Failure::StepTooLarge { step } => {
+                write!(f, "diff value is {}", step)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Safe,
+    /// Safe once the levels at `removed` are dropped; `violation` is the
+    /// first one in the full report.
+    Tolerated {
+        removed: Vec<usize>,
+        violation: Violation,
+    },
+    /// Not safe even with the tolerated removals; the violation is the first
+    /// one in the full report.
+    Unsafe(Violation),
+}
+
+impl Verdict {
+    pub fn is_safe(&self) -> bool {
+        !matches!(self, Verdict::Unsafe(_))
+    }
+
+    /// The first broken rule in the full report, if there was one.
+    pub fn violation(&self) -> Option<Violation> {
+        match self {
+            Verdict::Safe => None,
+            Verdict::Tolerated { violation, .. } | Verdict::Unsafe(violation) => Some(*violation),
+        }
+    }
+}
+
+/// What makes a report safe. The default is the part 1 rule: all increasing
+/// or all decreasing, in steps of 1 to 3, with no bad levels tolerated.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    monotonic: Monotonic,
+    steps: RangeInclusive<u32>,
+    max_bad_levels: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            monotonic: Monotonic::Either,
+            steps: 1..=3,
+            max_bad_levels: 0,
+        }
+    }
+}
+
+impl Rules {
+    pub fn with_monotonic(mut self, monotonic: Monotonic) -> Self {
+        self.monotonic = monotonic;
+        self
+    }
+
+    /// The allowed size of the difference between neighbouring levels.
+    pub fn with_steps(mut self, steps: RangeInclusive<u32>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// How many levels may be dropped to make a report safe.
+    pub fn with_max_bad_levels(mut self, max_bad_levels: usize) -> Self {
+        self.max_bad_levels = max_bad_levels;
+        self
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub fn check(&self, report: &[i32]) -> Verdict {
+        let Some(violation) = self.violation(report) else {
+            return Verdict::Safe;
+        };
+        match self.fewest_removals(report) {
+            Some(removed) => Verdict::Tolerated { removed, violation },
+            None => Verdict::Unsafe(violation),
+        }
+    }
+
+    /// The smallest set of levels to drop to make `report` safe, if it has no
+    /// more than `max_bad_levels` of them. Between equally small sets, later
+    /// levels are kept in preference to earlier ones.
+    ///
+    /// With the direction fixed, whether a report is safe only depends on
+    /// each pair of neighbouring kept levels, so this is a shortest path over
+    /// the levels: `cost[i]` is the fewest drops among levels `0..i` that
+    /// keeps level `i` last. Only the `max_bad_levels + 1` levels before `i`
+    /// can precede it, which makes this O(n * max_bad_levels) per direction.
+    fn fewest_removals(&self, report: &[i32]) -> Option<Vec<usize>> {
+        let directions = match self.monotonic {
+            Monotonic::Either => vec![Some(Direction::Increasing), Some(Direction::Decreasing)],
+            Monotonic::Only(direction) => vec![Some(direction)],
+            Monotonic::Unconstrained => vec![None],
+        };
+        directions
+            .into_iter()
+            .filter_map(|direction| self.fewest_removals_heading(report, direction))
+            .min_by_key(Vec::len)
+    }
+
+    fn fewest_removals_heading(
+        &self,
+        report: &[i32],
+        direction: Option<Direction>,
+    ) -> Option<Vec<usize>> {
+        let max = self.max_bad_levels;
+        let n = report.len();
+        if n <= 1 {
+            return Some(Vec::new());
+        }
+
+        // Dropping every level before `i` is always an option.
+        let mut cost = (0..n).collect::<Vec<_>>();
+        let mut previous: Vec<Option<usize>> = vec![None; n];
+        for i in 1..n {
+            for j in i.saturating_sub(max + 1)..i {
+                let drops = cost[j] + (i - j - 1);
+                // `<=` so that on a tie the nearer level is kept, and the
+                // earlier one dropped.
+                if drops <= cost[i] && self.allows(report[j], report[i], direction) {
+                    cost[i] = drops;
+                    previous[i] = Some(j);
+                }
+            }
+        }
+
+        let last = (0..n)
+            .filter(|&i| cost[i] + (n - 1 - i) <= max)
+            .min_by_key(|&i| (cost[i] + (n - 1 - i), Reverse(i)))?;
+        let mut kept = vec![false; n];
+        let mut at = Some(last);
+        while let Some(i) = at {
+            kept[i] = true;
+            at = previous[i];
+        }
+        Some((0..n).filter(|&i| !kept[i]).collect())
+    }
+
+    /// Whether `b` may directly follow `a` in a report heading `direction`.
+    fn allows(&self, a: i32, b: i32, direction: Option<Direction>) -> bool {
+        let heading = heading(a, b);
+        let direction_ok = match (direction, heading) {
+            (Some(expected), Some(heading)) => heading == expected,
+            _ => true,
+        };
+        direction_ok && self.steps.contains(&a.abs_diff(b))
+    }
+
+    /// The first broken rule in `report`, dropping nothing.
+    pub fn violation(&self, report: &[i32]) -> Option<Violation> {
+        self.first_violation(report, &[])
+    }
+
+    /// The first broken rule in `report` with the levels at `skip` left out.
+    fn first_violation(&self, report: &[i32], skip: &[usize]) -> Option<Violation> {
+        let mut direction = match self.monotonic {
+            Monotonic::Only(direction) => Some(direction),
+            Monotonic::Either | Monotonic::Unconstrained => None,
+        };
+
+        let levels = report
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(index, _)| !skip.contains(index));
+        for ((index, a), (_, b)) in levels.tuple_windows() {
+            let violation = |failure| {
+                Some(Violation {
+                    index,
+                    levels: (a, b),
+                    failure,
+                })
+            };
+
+            let step = a.abs_diff(b);
+            let heading = heading(a, b);
+            if self.monotonic != Monotonic::Unconstrained {
+                match (direction, heading) {
+                    (Some(expected), Some(heading)) if heading != expected => {
+                        return violation(Failure::Switched { expected });
+                    }
+                    (None, Some(_)) => direction = heading,
+                    _ => {}
+                }
+            }
+            if step < *self.steps.start() {
+                return violation(Failure::StepTooSmall { step });
+            }
+            if step > *self.steps.end() {
+                return violation(Failure::StepTooLarge { step });
+            }
+        }
+        None
+    }
+}
+
+/// Which way the levels go from `a` to `b`, if they change.
+fn heading(a: i32, b: i32) -> Option<Direction> {
+    match b.cmp(&a) {
+        Ordering::Greater => Some(Direction::Increasing),
+        Ordering::Less => Some(Direction::Decreasing),
+        Ordering::Equal => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Tries every set of removals, smallest first.
+    fn brute_force(rules: &Rules, report: &[i32]) -> Option<Vec<usize>> {
+        (0..=rules.max_bad_levels.min(report.len()))
+            .flat_map(|count| (0..report.len()).combinations(count))
+            .find(|removed| rules.first_violation(report, removed).is_none())
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let rules = Rules::default();
+        assert_eq!(Verdict::Safe, rules.check(&[7, 6, 4, 2, 1]));
+        assert_eq!(
+            Verdict::Unsafe(Violation {
+                index: 1,
+                levels: (2, 7),
+                failure: Failure::StepTooLarge { step: 5 },
+            }),
+            rules.check(&[1, 2, 7, 8, 9])
+        );
+        assert_eq!(
+            Verdict::Unsafe(Violation {
+                index: 1,
+                levels: (3, 2),
+                failure: Failure::Switched {
+                    expected: Direction::Increasing
+                },
+            }),
+            rules.check(&[1, 3, 2, 4, 5])
+        );
+        assert_eq!(
+            Verdict::Unsafe(Violation {
+                index: 2,
+                levels: (4, 4),
+                failure: Failure::StepTooSmall { step: 0 },
+            }),
+            rules.check(&[8, 6, 4, 4, 1])
+        );
+    }
+
+    #[test]
+    fn test_first_step_is_checked() {
+        let verdict = Rules::default().check(&[1, 7, 8]);
+        assert!(matches!(
+            verdict,
+            Verdict::Unsafe(Violation {
+                index: 0,
+                failure: Failure::StepTooLarge { step: 6 },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_extreme_levels() {
+        let rules = Rules::default().with_max_bad_levels(1);
+        assert_eq!(
+            Verdict::Unsafe(Violation {
+                index: 0,
+                levels: (i32::MIN, i32::MAX),
+                failure: Failure::StepTooLarge { step: u32::MAX },
+            }),
+            rules.check(&[i32::MIN, i32::MAX, i32::MIN])
+        );
+        let rules = Rules::default().with_steps(1..=u32::MAX);
+        assert_eq!(Verdict::Safe, rules.check(&[i32::MIN, 0, i32::MAX]));
+    }
+
+    fn removed(verdict: Verdict) -> Option<Vec<usize>> {
+        match verdict {
+            Verdict::Tolerated { removed, .. } => Some(removed),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let rules = Rules::default().with_max_bad_levels(1);
+        assert_eq!(
+            Verdict::Tolerated {
+                removed: vec![1],
+                violation: Violation {
+                    index: 1,
+                    levels: (3, 2),
+                    failure: Failure::Switched {
+                        expected: Direction::Increasing
+                    },
+                },
+            },
+            rules.check(&[1, 3, 2, 4, 5])
+        );
+        assert!(!rules.check(&[9, 7, 6, 2, 1]).is_safe());
+
+        let rules = rules.with_max_bad_levels(2);
+        assert_eq!(Some(vec![1, 2]), removed(rules.check(&[1, 9, 9, 2, 3])));
+    }
+
+    #[test]
+    fn test_configured_rules() {
+        let rules = Rules::default().with_monotonic(Monotonic::Only(Direction::Decreasing));
+        assert!(!rules.check(&[1, 2, 3]).is_safe());
+        assert!(rules.check(&[3, 2, 1]).is_safe());
+
+        let rules = Rules::default()
+            .with_monotonic(Monotonic::Unconstrained)
+            .with_steps(0..=5);
+        assert!(rules.check(&[1, 1, 6, 2]).is_safe());
+        assert!(!rules.check(&[1, 7]).is_safe());
+    }
+
+    #[test]
+    fn test_tells_which_level_to_drop() {
+        let rules = Rules::default().with_max_bad_levels(1);
+        assert_eq!(Some(vec![2]), removed(rules.check(&[8, 6, 4, 4, 1])));
+        assert_eq!(Some(vec![0]), removed(rules.check(&[9, 1, 2, 3])));
+        assert_eq!(Some(vec![3]), removed(rules.check(&[1, 2, 3, 9])));
+    }
+
+    fn monotonic() -> impl Strategy<Value = Monotonic> {
+        prop_oneof![
+            Just(Monotonic::Either),
+            Just(Monotonic::Only(Direction::Increasing)),
+            Just(Monotonic::Only(Direction::Decreasing)),
+            Just(Monotonic::Unconstrained),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_agrees_with_brute_force(
+            report in prop::collection::vec(0..12i32, 0..9),
+            monotonic in monotonic(),
+            min_step in 0..3u32,
+            extra in 0..4u32,
+            max_bad_levels in 0..4usize,
+        ) {
+            let rules = Rules::default()
+                .with_monotonic(monotonic)
+                .with_steps(min_step..=min_step + extra)
+                .with_max_bad_levels(max_bad_levels);
+
+            let expected = brute_force(&rules, &report);
+            let actual = rules.fewest_removals(&report);
+            prop_assert_eq!(expected.as_ref().map(Vec::len), actual.as_ref().map(Vec::len));
+            if let Some(removed) = &actual {
+                prop_assert!(rules.first_violation(&report, removed).is_none());
+            }
+            prop_assert_eq!(expected.is_some(), rules.check(&report).is_safe());
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use aoc_core::Solution;
+use day_03::scan::{self, ReadScanner};
+use day_03::*;
+
+fn main() {
+    // Run registered benchmarks.
+    divan::main();
+}
+
+const INPUT: &str = include_str!("../input.txt");
+
+#[divan::bench]
+fn bench_parse() {
+    Day03::parse(divan::black_box(INPUT)).unwrap();
+}
+
+#[divan::bench]
+fn bench_part1(bencher: divan::Bencher) {
+    let input = Day03::parse(INPUT).unwrap();
+    bencher.bench(|| Day03::solve_part1(divan::black_box(&input)).unwrap());
+}
+
+#[divan::bench]
+fn bench_part2(bencher: divan::Bencher) {
+    let input = Day03::parse(INPUT).unwrap();
+    bencher.bench(|| Day03::solve_part2(divan::black_box(&input)).unwrap());
+}
+
+#[divan::bench(args = [false, true])]
+fn bench_scan(conditional: bool) -> u64 {
+    scan::total(scan::scan(divan::black_box(INPUT.as_bytes())), conditional)
+}
+
+#[divan::bench(args = [false, true])]
+fn bench_scan_reader(conditional: bool) -> u64 {
+    let found = ReadScanner::new(divan::black_box(INPUT.as_bytes())).map(Result::unwrap);
+    scan::total(found, conditional)
+}
+
+#[divan::bench]
+fn bench_nom_part1() -> i32 {
+    nom_parser::part_1::total(divan::black_box(INPUT))
+}
+
+#[divan::bench(args = [false, true])]
+fn bench_nom_part2(conditional: bool) -> i32 {
+    let instructions = nom_parser::part_2::parse(divan::black_box(INPUT));
+    nom_parser::part_2::total(&instructions, conditional)
+}
+
+/// The parsers day 3 used before it had its own lexer, kept to measure the
+/// scanner against.
+mod nom_parser {
+    /// Skips to each `m` with `is_not`, stepping over one character with
+    /// `take` whenever `mul(` doesn't follow.
+    pub mod part_1 {
+        use nom::{
+            bytes::complete::{is_a, is_not, tag, take},
+            character::complete::digit1,
+            multi::many0,
+            sequence::preceded,
+            IResult,
+        };
+
+        type NomErr<'a> = nom::error::Error<&'a str>;
+
+        pub fn total(input: &str) -> i32 {
+            many0(parse_mul)(input).unwrap().1.iter().sum()
+        }
+
+        fn parse_number(input: &str) -> IResult<&str, i32> {
+            let (next, num) = digit1(input)?;
+            Ok((next, num.parse::<i32>().unwrap()))
+        }
+
+        fn parse_mul(input: &str) -> IResult<&str, i32> {
+            let Ok((next, _)) =
+                preceded(many0(is_not::<&str, &str, NomErr>("m")), tag("mul"))(input)
+            else {
+                let (next, _) = take(1usize)(input)?;
+                return Ok((next, 0));
+            };
+            let Ok((next, _)) = is_a::<&str, &str, NomErr>("(")(next) else {
+                return Ok((next, 0));
+            };
+            let (next, first) = parse_number(next)?;
+            let Ok((next, _)) = is_a::<&str, &str, NomErr>(",")(next) else {
+                return Ok((next, 0));
+            };
+            let (next, second) = parse_number(next)?;
+            let Ok((next, _)) = is_a::<&str, &str, NomErr>(")")(next) else {
+                return Ok((next, 0));
+            };
+            Ok((next, first * second))
+        }
+    }
+
+    /// Tries every instruction at each character in turn with `many_till`.
+    pub mod part_2 {
+        use nom::{
+            branch::alt,
+            bytes::complete::tag,
+            character::complete::{anychar, digit1},
+            combinator::{map, map_res, rest, value},
+            multi::{many0, many_till},
+            sequence::{delimited, separated_pair, terminated},
+            IResult,
+        };
+
+        #[derive(Clone)]
+        pub enum Keyword {
+            Mul(i32),
+            Do,
+            Dont,
+        }
+
+        pub fn parse(input: &str) -> Vec<Keyword> {
+            parse_instructions(input).unwrap().1
+        }
+
+        pub fn total(instructions: &[Keyword], conditional: bool) -> i32 {
+            let mut enabled = true;
+            let mut total = 0;
+            for instruction in instructions {
+                match instruction {
+                    Keyword::Mul(product) if enabled => total += product,
+                    Keyword::Mul(_) => {}
+                    Keyword::Do => enabled = true,
+                    Keyword::Dont => enabled = !conditional,
+                }
+            }
+            total
+        }
+
+        fn parse_instructions(input: &str) -> IResult<&str, Vec<Keyword>> {
+            terminated(
+                many0(map(many_till(anychar, parse_instruction), |value| value.1)),
+                rest,
+            )(input)
+        }
+
+        fn parse_instruction(input: &str) -> IResult<&str, Keyword> {
+            alt((
+                value(Keyword::Do, tag("do()")),
+                value(Keyword::Dont, tag("don't()")),
+                parse_mul,
+            ))(input)
+        }
+
+        fn parse_mul(input: &str) -> IResult<&str, Keyword> {
+            let (next, _) = tag("mul")(input)?;
+
+            let (next, result) = delimited(
+                tag("("),
+                separated_pair(parse_number, tag(","), parse_number),
+                tag(")"),
+            )(next)?;
+
+            Ok((next, Keyword::Mul(result.0 * result.1)))
+        }
+
+        fn parse_number(input: &str) -> IResult<&str, i32> {
+            map_res(digit1, str::parse::<i32>)(input)
+        }
+    }
+}
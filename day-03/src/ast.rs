@@ -0,0 +1,205 @@
+use crate::lexer::{Lexer, Token, TokenKind};
+use aoc_core::parse::ParseError;
+use std::fmt;
+use std::ops::Range;
+
+/// A well-formed `name(arg,...)` found in the memory. Whether `name` means
+/// anything is up to the interpreter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call<'a> {
+    pub name: &'a str,
+    pub args: Vec<i64>,
+    /// Bytes from the start of `name` to the closing `)`.
+    pub span: Range<usize>,
+}
+
+impl fmt::Display for Call<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (index, arg) in self.args.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Program<'a> {
+    pub source: &'a str,
+    pub calls: Vec<Call<'a>>,
+}
+
+/// The longest operand strict parsing accepts.
+pub const MAX_DIGITS: usize = 3;
+
+/// Finds every call in `source`. Corruption runs straight into names, as in
+/// `do_not_mul(5,5)`, so a word ending in one of `known` is read as that
+/// name, taking the longest that fits. Other words are kept whole, as calls
+/// to unknown instructions.
+///
+/// If `strict`, a call with an operand over `MAX_DIGITS` digits is corrupt.
+/// Otherwise operands can be any length, and one that does not fit in 64 bits
+/// is an error rather than silently dropped.
+pub fn parse<'a>(source: &'a str, known: &[&str], strict: bool) -> Result<Program<'a>, ParseError> {
+    let tokens = Lexer::new(source).collect::<Vec<_>>();
+    let mut calls = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        match call(&tokens[index..], known, strict) {
+            Some((call, used)) => {
+                calls.push(call.into_call(source)?);
+                index += used;
+            }
+            None => index += 1,
+        }
+    }
+    Ok(Program { source, calls })
+}
+
+/// A call whose operands have not been converted yet.
+struct RawCall<'a, 't> {
+    name: &'a str,
+    args: Vec<&'t Token<'a>>,
+    span: Range<usize>,
+}
+
+impl<'a> RawCall<'a, '_> {
+    fn into_call(self, source: &str) -> Result<Call<'a>, ParseError> {
+        let args = self
+            .args
+            .into_iter()
+            .map(|arg| {
+                arg.text.parse().map_err(|_| {
+                    ParseError::new(source, arg.offset, "an operand that fits in 64 bits")
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Call {
+            name: self.name,
+            args,
+            span: self.span,
+        })
+    }
+}
+
+/// A call at the start of `tokens`, and how many tokens it took.
+fn call<'a, 't>(
+    tokens: &'t [Token<'a>],
+    known: &[&str],
+    strict: bool,
+) -> Option<(RawCall<'a, 't>, usize)> {
+    let [word, open, rest @ ..] = tokens else {
+        return None;
+    };
+    if word.kind != TokenKind::Word || open.kind != TokenKind::Open {
+        return None;
+    }
+
+    let mut args = Vec::new();
+    let mut used = 2;
+    let mut rest = rest.iter();
+    let close = loop {
+        let token = rest.next()?;
+        used += 1;
+        match token.kind {
+            TokenKind::Close if args.is_empty() => break token,
+            TokenKind::Number if strict && token.text.len() > MAX_DIGITS => return None,
+            TokenKind::Number => args.push(token),
+            _ => return None,
+        }
+        let token = rest.next()?;
+        used += 1;
+        match token.kind {
+            TokenKind::Close => break token,
+            TokenKind::Comma => {}
+            _ => return None,
+        }
+    };
+
+    let name = known
+        .iter()
+        .filter(|name| word.text.ends_with(*name))
+        .max_by_key(|name| name.len())
+        .map_or(word.text, |name| &word.text[word.text.len() - name.len()..]);
+    let start = word.span().end - name.len();
+    let call = RawCall {
+        name,
+        args,
+        span: start..close.span().end,
+    };
+    Some((call, used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KNOWN: [&str; 3] = ["mul", "do", "don't"];
+
+    fn calls(source: &str) -> Vec<(String, usize)> {
+        parse(source, &KNOWN, false)
+            .unwrap()
+            .calls
+            .iter()
+            .map(|call| (call.to_string(), call.span.start))
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_calls_in_corruption() {
+        assert_eq!(
+            vec![
+                ("mul(2,4)".to_string(), 1),
+                ("mul(5,5)".to_string(), 29),
+                ("mul(11,8)".to_string(), 53),
+                ("mul(8,5)".to_string(), 62),
+            ],
+            calls("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))")
+        );
+    }
+
+    #[test]
+    fn test_known_names_are_suffixes() {
+        assert_eq!(
+            vec![
+                ("don't()".to_string(), 1),
+                ("do()".to_string(), 11),
+                ("what(1)".to_string(), 16),
+            ],
+            calls("xdon't()_undo()?what(1)")
+        );
+    }
+
+    #[test]
+    fn test_malformed_calls() {
+        assert!(calls("mul(1,)mul(,1)mul(1 2)mul(1,2").is_empty());
+        assert_eq!(vec![("mul(3,4)".to_string(), 4)], calls("mul(mul(3,4)"));
+    }
+
+    #[test]
+    fn test_strict_operands() {
+        let source = "mul(1234,5)mul(123,45)";
+        let strict = parse(source, &KNOWN, true).unwrap();
+        assert_eq!(
+            vec![vec![123, 45]],
+            strict
+                .calls
+                .iter()
+                .map(|c| c.args.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, parse(source, &KNOWN, false).unwrap().calls.len());
+    }
+
+    #[test]
+    fn test_operand_out_of_range() {
+        let source = "mul(2,4)\nmul(99999999999999999999,1)mul(999999999999999999999";
+        let error = parse(source, &KNOWN, false).unwrap_err();
+        assert_eq!((2, 5), (error.line, error.column));
+        assert_eq!("an operand that fits in 64 bits", error.expected);
+        assert_eq!(1, parse(source, &KNOWN, true).unwrap().calls.len());
+    }
+}
@@ -0,0 +1,421 @@
+use crate::ast::{self, Call, Program};
+use aoc_core::parse::ParseError;
+use miette::{miette, LabeledSpan};
+use num_bigint::BigInt;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// How the machine keeps its running total.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Arithmetic {
+    /// 64 bits, and overflowing is an error.
+    #[default]
+    Checked,
+    /// 64 bits, wrapping around on overflow.
+    Wrapping,
+    /// As many bits as it takes.
+    Big,
+}
+
+#[derive(Debug, Clone)]
+pub enum Total {
+    Fixed(i64),
+    Big(BigInt),
+}
+
+impl Total {
+    fn to_big(&self) -> BigInt {
+        match self {
+            Total::Fixed(value) => BigInt::from(*value),
+            Total::Big(value) => value.clone(),
+        }
+    }
+}
+
+impl From<i64> for Total {
+    fn from(value: i64) -> Self {
+        Total::Fixed(value)
+    }
+}
+
+/// Totals are equal if their values are, however they are kept.
+impl PartialEq for Total {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Total::Fixed(a), Total::Fixed(b)) => a == b,
+            _ => self.to_big() == other.to_big(),
+        }
+    }
+}
+
+impl Eq for Total {}
+
+impl fmt::Display for Total {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Total::Fixed(value) => write!(f, "{}", value),
+            Total::Big(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// The total no longer fits under `Arithmetic::Checked`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+/// The state instructions act on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    arithmetic: Arithmetic,
+    total: Total,
+    pub enabled: bool,
+    blocks: Vec<bool>,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self::new(Arithmetic::default())
+    }
+}
+
+impl Machine {
+    pub fn new(arithmetic: Arithmetic) -> Self {
+        let total = match arithmetic {
+            Arithmetic::Checked | Arithmetic::Wrapping => Total::Fixed(0),
+            Arithmetic::Big => Total::Big(BigInt::ZERO),
+        };
+        Machine {
+            arithmetic,
+            total,
+            enabled: true,
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn total(&self) -> &Total {
+        &self.total
+    }
+
+    pub fn add(&mut self, value: i64) -> Result<(), Overflow> {
+        self.add_product(value, 1)
+    }
+
+    /// Adds `a * b`. Under `Arithmetic::Checked` the total is left as it was
+    /// if either step overflows.
+    pub fn add_product(&mut self, a: i64, b: i64) -> Result<(), Overflow> {
+        match (&mut self.total, self.arithmetic) {
+            (Total::Big(total), _) => *total += BigInt::from(a) * b,
+            (Total::Fixed(total), Arithmetic::Wrapping) => {
+                *total = total.wrapping_add(a.wrapping_mul(b));
+            }
+            (Total::Fixed(total), _) => {
+                *total = a
+                    .checked_mul(b)
+                    .and_then(|product| total.checked_add(product))
+                    .ok_or(Overflow)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens a block that is only enabled if `condition` holds and the
+    /// machine already was.
+    pub fn push_block(&mut self, condition: bool) {
+        self.blocks.push(self.enabled);
+        self.enabled &= condition;
+    }
+
+    /// Closes the innermost block, restoring whether the machine was enabled
+    /// before it. An unmatched close does nothing.
+    pub fn pop_block(&mut self) {
+        if let Some(enabled) = self.blocks.pop() {
+            self.enabled = enabled;
+        }
+    }
+}
+
+type Action = Box<dyn Fn(&[i64], &mut Machine) -> Result<(), Overflow>>;
+
+struct Instruction {
+    arity: usize,
+    action: Action,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Ran,
+    /// No instruction of that name is registered.
+    Unknown,
+    WrongArity {
+        expected: usize,
+    },
+}
+
+/// One call and the machine state right after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<'p> {
+    pub call: &'p Call<'p>,
+    pub outcome: Outcome,
+    pub total: Total,
+    pub enabled: bool,
+}
+
+impl fmt::Display for Step<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "@{:<6} {:<16} ",
+            self.call.span.start,
+            self.call.to_string()
+        )?;
+        match self.outcome {
+            Outcome::Ran => write!(f, "ran")?,
+            Outcome::Unknown => write!(f, "skipped, unknown")?,
+            Outcome::WrongArity { expected } => write!(f, "skipped, takes {} arguments", expected)?,
+        }
+        let state = if self.enabled { "enabled" } else { "disabled" };
+        write!(f, "  total {}, {}", self.total, state)
+    }
+}
+
+/// Runs programs against a set of named instructions. Calls to anything else
+/// are skipped, so the same memory can be run under different rules.
+#[derive(Default)]
+pub struct Interpreter {
+    instructions: BTreeMap<String, Instruction>,
+    arithmetic: Arithmetic,
+    strict: bool,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Part 1: only `mul` means anything.
+    pub fn part_1() -> Self {
+        Self::new().register("mul", 2, mul)
+    }
+
+    /// Part 2: `do()` and `don't()` switch `mul` on and off.
+    pub fn part_2() -> Self {
+        Self::part_1()
+            .register("do", 0, |_, machine| {
+                machine.enabled = true;
+                Ok(())
+            })
+            .register("don't", 0, |_, machine| {
+                machine.enabled = false;
+                Ok(())
+            })
+    }
+
+    /// Adds or replaces an instruction. `action` is called for every call
+    /// with exactly `arity` arguments, enabled or not, so instructions that
+    /// should respect `don't()` have to check `machine.enabled`.
+    pub fn register(
+        mut self,
+        name: impl Into<String>,
+        arity: usize,
+        action: impl Fn(&[i64], &mut Machine) -> Result<(), Overflow> + 'static,
+    ) -> Self {
+        let instruction = Instruction {
+            arity,
+            action: Box::new(action),
+        };
+        self.instructions.insert(name.into(), instruction);
+        self
+    }
+
+    pub fn with_arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.arithmetic = arithmetic;
+        self
+    }
+
+    /// Treat calls with operands over `ast::MAX_DIGITS` digits as corrupt,
+    /// as the puzzle does.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.instructions.keys().map(String::as_str).collect()
+    }
+
+    /// Parses `source` with this interpreter's instruction names.
+    pub fn parse<'a>(&self, source: &'a str) -> Result<Program<'a>, ParseError> {
+        ast::parse(source, &self.names(), self.strict)
+    }
+
+    pub fn run(&self, program: &Program) -> miette::Result<Total> {
+        let mut machine = Machine::new(self.arithmetic);
+        for call in &program.calls {
+            self.step(program, call, &mut machine)?;
+        }
+        Ok(machine.total)
+    }
+
+    /// Like `run`, but records every step.
+    pub fn trace<'p>(&self, program: &'p Program<'p>) -> miette::Result<Vec<Step<'p>>> {
+        let mut machine = Machine::new(self.arithmetic);
+        program
+            .calls
+            .iter()
+            .map(|call| {
+                Ok(Step {
+                    call,
+                    outcome: self.step(program, call, &mut machine)?,
+                    total: machine.total.clone(),
+                    enabled: machine.enabled,
+                })
+            })
+            .collect()
+    }
+
+    fn step(
+        &self,
+        program: &Program,
+        call: &Call,
+        machine: &mut Machine,
+    ) -> miette::Result<Outcome> {
+        let Some(instruction) = self.instructions.get(call.name) else {
+            return Ok(Outcome::Unknown);
+        };
+        if call.args.len() != instruction.arity {
+            return Ok(Outcome::WrongArity {
+                expected: instruction.arity,
+            });
+        }
+        (instruction.action)(&call.args, machine).map_err(|Overflow| {
+            miette!(
+                code = "day03::overflow",
+                labels = vec![LabeledSpan::at(call.span.clone(), "overflows here")],
+                help = "use wrapping or big-integer arithmetic",
+                "the total of {} no longer fits in 64 bits after {}",
+                machine.total,
+                call
+            )
+            .with_source_code(program.source.to_string())
+        })?;
+        Ok(Outcome::Ran)
+    }
+}
+
+fn mul(args: &[i64], machine: &mut Machine) -> Result<(), Overflow> {
+    if machine.enabled {
+        machine.add_product(args[0], args[1])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_builtins() -> miette::Result<()> {
+        let program = Interpreter::part_2().parse(EXAMPLE)?;
+        assert_eq!(Total::from(161), Interpreter::part_1().run(&program)?);
+        assert_eq!(Total::from(48), Interpreter::part_2().run(&program)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace() -> miette::Result<()> {
+        let interpreter = Interpreter::part_2();
+        let program = interpreter.parse("mul(2,3)don't()mul(4,5)do(1)")?;
+        let trace = interpreter.trace(&program)?;
+        let outcomes = trace
+            .iter()
+            .map(|step| {
+                (
+                    step.call.span.start,
+                    step.outcome,
+                    step.total.to_string(),
+                    step.enabled,
+                )
+            })
+            .collect::<Vec<_>>();
+        let six = || "6".to_string();
+        assert_eq!(
+            vec![
+                (0, Outcome::Ran, six(), true),
+                (8, Outcome::Ran, six(), false),
+                (15, Outcome::Ran, six(), false),
+                (23, Outcome::WrongArity { expected: 0 }, six(), false),
+            ],
+            outcomes
+        );
+        assert_eq!(
+            "@23     do(1)            skipped, takes 0 arguments  total 6, disabled",
+            trace[3].to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_instructions() -> miette::Result<()> {
+        let interpreter = Interpreter::part_2()
+            .register("add", 2, |args, machine| {
+                if machine.enabled {
+                    machine.add(args[0])?;
+                    machine.add(args[1])?;
+                }
+                Ok(())
+            })
+            .register("if", 1, |args, machine| {
+                machine.push_block(args[0] != 0);
+                Ok(())
+            })
+            .register("end", 0, |_, machine| {
+                machine.pop_block();
+                Ok(())
+            });
+
+        let program =
+            interpreter.parse("add(1,2)if(0)mul(10,10)if(1)add(5,5)end()end()mul(2,2)")?;
+        assert_eq!(Total::from(7), interpreter.run(&program)?);
+        Ok(())
+    }
+
+    const LARGE: &str = "mul(9223372036854775807,2)mul(1,3)";
+
+    #[test]
+    fn test_checked_overflow() -> miette::Result<()> {
+        let interpreter = Interpreter::part_1();
+        let error = interpreter.run(&interpreter.parse(LARGE)?).unwrap_err();
+        assert_eq!(
+            "the total of 0 no longer fits in 64 bits after mul(9223372036854775807,2)",
+            error.to_string()
+        );
+        let label = error.labels().unwrap().next().unwrap();
+        assert_eq!((0, 26), (label.offset(), label.len()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrapping_and_big() -> miette::Result<()> {
+        let program = Interpreter::part_1().parse(LARGE)?;
+        let total = |arithmetic| {
+            Interpreter::part_1()
+                .with_arithmetic(arithmetic)
+                .run(&program)
+        };
+        assert_eq!(Total::from(1), total(Arithmetic::Wrapping)?);
+        assert_eq!("18446744073709551617", total(Arithmetic::Big)?.to_string());
+        assert_eq!(Total::from(3), Total::Big(BigInt::from(3)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict() -> miette::Result<()> {
+        let interpreter = Interpreter::part_1().with_strict(true);
+        let program = interpreter.parse(LARGE)?;
+        assert_eq!(Total::from(3), interpreter.run(&program)?);
+        Ok(())
+    }
+}
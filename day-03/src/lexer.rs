@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A run of letters, `_` and `'`, so `don't` is one word.
+    Word,
+    /// A run of ASCII digits.
+    Number,
+    Open,
+    Close,
+    Comma,
+    /// Any other character: the corruption between instructions.
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// Byte offset of `text` in the source.
+    pub offset: usize,
+}
+
+impl Token<'_> {
+    pub fn span(&self) -> Range<usize> {
+        self.offset..self.offset + self.text.len()
+    }
+}
+
+/// Splits the source into tokens. Every byte belongs to exactly one token,
+/// so nothing is lost and offsets always point back into the source.
+pub struct Lexer<'a> {
+    source: &'a str,
+    offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Lexer { source, offset: 0 }
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '\''
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.source[self.offset..];
+        let first = rest.chars().next()?;
+        let (kind, len) = match first {
+            '(' => (TokenKind::Open, 1),
+            ')' => (TokenKind::Close, 1),
+            ',' => (TokenKind::Comma, 1),
+            c if c.is_ascii_digit() => {
+                let len = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                (TokenKind::Number, len)
+            }
+            c if is_word(c) => {
+                let len = rest.find(|c| !is_word(c)).unwrap_or(rest.len());
+                (TokenKind::Word, len)
+            }
+            c => (TokenKind::Other, c.len_utf8()),
+        };
+
+        let token = Token {
+            kind,
+            text: &rest[..len],
+            offset: self.offset,
+        };
+        self.offset += len;
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TokenKind::*;
+
+    #[test]
+    fn test_tokens() {
+        let tokens = Lexer::new("xdon't(12,3)]é")
+            .map(|t| (t.kind, t.text, t.offset))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (Word, "xdon't", 0),
+                (Open, "(", 6),
+                (Number, "12", 7),
+                (Comma, ",", 9),
+                (Number, "3", 10),
+                (Close, ")", 11),
+                (Other, "]", 12),
+                (Word, "é", 13),
+            ],
+            tokens
+        );
+    }
+}
@@ -0,0 +1,39 @@
+pub mod ast;
+pub mod eval;
+pub mod lexer;
+pub mod part_1;
+pub mod part_2;
+pub mod scan;
+
+use aoc_core::{Day, Solution};
+use ast::Program;
+use eval::{Interpreter, Total};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input<'a> = Program<'a>;
+    type Output = Total;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        part_1::process(input)
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        part_2::process(input)
+    }
+}
+
+pub const DAY: Day = Day::new::<Day03>(3, env!("CARGO_MANIFEST_DIR"));
+
+/// Everything between and after instructions is corrupted memory, and
+/// operands over three digits are too, so this never fails. Names are read
+/// with every built-in instruction known, so both parts can run the same
+/// program.
+pub fn parse(input: &str) -> miette::Result<Program<'_>> {
+    Ok(Interpreter::part_2().with_strict(true).parse(input)?)
+}
@@ -0,0 +1,260 @@
+use std::io::{self, Read};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Found {
+    pub op: Op,
+    /// Byte offset of the instruction's first character.
+    pub offset: usize,
+}
+
+/// How much of an instruction has been seen so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    M,
+    Mu,
+    Mul,
+    First,
+    Second,
+    D,
+    Do,
+    DoOpen,
+    Don,
+    DonQuote,
+    DonT,
+    DontOpen,
+}
+
+const MAX_DIGITS: u8 = 3;
+
+/// Finds `mul(X,Y)` with 1-3 digit operands, `do()` and `don't()` one byte
+/// at a time. No instruction can start partway through a partial match of
+/// another, so a byte that breaks a match only ever needs to be retried as
+/// the start of a new one: nothing is read twice and nothing is buffered.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    state: State,
+    first: u32,
+    operand: u32,
+    digits: u8,
+    position: usize,
+    start: usize,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Scanner {
+            state: State::Start,
+            first: 0,
+            operand: 0,
+            digits: 0,
+            position: 0,
+            start: 0,
+        }
+    }
+}
+
+impl Scanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn feed(&mut self, byte: u8) -> Option<Found> {
+        let position = self.position;
+        self.position += 1;
+        let next = match (self.state, byte) {
+            (State::Start, _) => return self.restart(byte, position),
+            (State::M, b'u') => State::Mu,
+            (State::Mu, b'l') => State::Mul,
+            (State::Mul, b'(') => {
+                self.operand = 0;
+                self.digits = 0;
+                State::First
+            }
+            (State::First | State::Second, b'0'..=b'9') if self.digits < MAX_DIGITS => {
+                self.operand = self.operand * 10 + u32::from(byte - b'0');
+                self.digits += 1;
+                self.state
+            }
+            (State::First, b',') if self.digits > 0 => {
+                self.first = self.operand;
+                self.operand = 0;
+                self.digits = 0;
+                State::Second
+            }
+            (State::Second, b')') if self.digits > 0 => {
+                return self.found(Op::Mul(self.first, self.operand));
+            }
+            (State::D, b'o') => State::Do,
+            (State::Do, b'(') => State::DoOpen,
+            (State::DoOpen, b')') => return self.found(Op::Do),
+            (State::Do, b'n') => State::Don,
+            (State::Don, b'\'') => State::DonQuote,
+            (State::DonQuote, b't') => State::DonT,
+            (State::DonT, b'(') => State::DontOpen,
+            (State::DontOpen, b')') => return self.found(Op::Dont),
+            _ => return self.restart(byte, position),
+        };
+        self.state = next;
+        None
+    }
+
+    /// Treats `byte` as a possible start of a new instruction.
+    fn restart(&mut self, byte: u8, position: usize) -> Option<Found> {
+        self.state = match byte {
+            b'm' => State::M,
+            b'd' => State::D,
+            _ => State::Start,
+        };
+        self.start = position;
+        None
+    }
+
+    fn found(&mut self, op: Op) -> Option<Found> {
+        self.state = State::Start;
+        Some(Found {
+            op,
+            offset: self.start,
+        })
+    }
+}
+
+pub fn scan(bytes: &[u8]) -> impl Iterator<Item = Found> + '_ {
+    let mut scanner = Scanner::new();
+    bytes.iter().filter_map(move |&byte| scanner.feed(byte))
+}
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Scans a reader through a fixed buffer, so memory use does not grow with
+/// the input.
+pub struct ReadScanner<R> {
+    reader: R,
+    scanner: Scanner,
+    buffer: [u8; BUFFER_SIZE],
+    filled: usize,
+    consumed: usize,
+}
+
+impl<R: Read> ReadScanner<R> {
+    pub fn new(reader: R) -> Self {
+        ReadScanner {
+            reader,
+            scanner: Scanner::new(),
+            buffer: [0; BUFFER_SIZE],
+            filled: 0,
+            consumed: 0,
+        }
+    }
+}
+
+impl<R: Read> Iterator for ReadScanner<R> {
+    type Item = io::Result<Found>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while self.consumed < self.filled {
+                let byte = self.buffer[self.consumed];
+                self.consumed += 1;
+                if let Some(found) = self.scanner.feed(byte) {
+                    return Some(Ok(found));
+                }
+            }
+            match self.reader.read(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(filled) => {
+                    self.filled = filled;
+                    self.consumed = 0;
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+/// Sums the products, honouring `do()` and `don't()` if `conditional`.
+pub fn total(found: impl IntoIterator<Item = Found>, conditional: bool) -> u64 {
+    let mut enabled = true;
+    let mut total = 0;
+    for found in found {
+        match found.op {
+            Op::Mul(a, b) if enabled => total += u64::from(a) * u64::from(b),
+            Op::Mul(..) => {}
+            Op::Do => enabled = true,
+            Op::Dont => enabled = !conditional,
+        }
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_scan() {
+        let found = scan(EXAMPLE.as_bytes()).collect::<Vec<_>>();
+        let expected = [
+            (Op::Mul(2, 4), 1),
+            (Op::Dont, 20),
+            (Op::Mul(5, 5), 28),
+            (Op::Mul(11, 8), 48),
+            (Op::Do, 59),
+            (Op::Mul(8, 5), 64),
+        ]
+        .map(|(op, offset)| Found { op, offset });
+        assert_eq!(expected.to_vec(), found);
+        assert_eq!(161, total(found.iter().copied(), false));
+        assert_eq!(48, total(found, true));
+    }
+
+    #[test]
+    fn test_digit_limit() {
+        let ops = |input: &str| scan(input.as_bytes()).map(|f| f.op).collect::<Vec<_>>();
+        assert_eq!(vec![Op::Mul(123, 999)], ops("mul(123,999)"));
+        assert!(ops("mul(1234,5)mul(5,1234)mul(,5)mul(5,)").is_empty());
+    }
+
+    #[test]
+    fn test_restarts_on_breaking_byte() {
+        let found = scan(b"mulmul(1,2)ddo()mul(3,d4)do()").collect::<Vec<_>>();
+        let expected = [(Op::Mul(1, 2), 3), (Op::Do, 12), (Op::Do, 25)]
+            .map(|(op, offset)| Found { op, offset });
+        assert_eq!(expected.to_vec(), found);
+    }
+
+    /// Hands out at most one byte per read, so every instruction straddles a
+    /// buffer boundary.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let Some((&first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buffer[0] = first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_read_scanner() -> io::Result<()> {
+        let streamed =
+            ReadScanner::new(Trickle(EXAMPLE.as_bytes())).collect::<io::Result<Vec<_>>>()?;
+        assert_eq!(scan(EXAMPLE.as_bytes()).collect::<Vec<_>>(), streamed);
+        Ok(())
+    }
+}
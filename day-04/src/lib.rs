@@ -0,0 +1,33 @@
+pub mod part_1;
+pub mod part_2;
+pub mod search;
+
+use aoc_core::{Day, Solution};
+use aoc_grid::Grid;
+
+pub type Matrix = Grid<char>;
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input<'a> = Matrix;
+    type Output = u32;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_1::process(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_2::process(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day04>(4, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<Matrix> {
+    Ok(Grid::parse(input, Ok)?)
+}
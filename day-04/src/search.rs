@@ -0,0 +1,231 @@
+use aoc_core::ParseError;
+use aoc_grid::{Coord, Direction, Grid};
+
+/// One word found in the grid, read from `start` in `direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'w> {
+    pub word: &'w str,
+    pub start: Coord,
+    pub direction: Direction,
+}
+
+/// Finds words and 2D templates in a grid of letters.
+pub struct WordSearch<'g> {
+    grid: &'g Grid<char>,
+    wrap: bool,
+}
+
+impl<'g> WordSearch<'g> {
+    pub fn new(grid: &'g Grid<char>) -> Self {
+        WordSearch { grid, wrap: false }
+    }
+
+    /// Lets words and templates run off one edge and continue from the
+    /// opposite one. A word never reuses a cell, so it still has to fit in
+    /// the cycle it is read along.
+    pub fn wrap_around(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Every occurrence of every word, in all eight directions, ordered by
+    /// start cell, then direction, then the order of `words`.
+    pub fn find<'w>(&self, words: &[&'w str]) -> Vec<Match<'w>> {
+        let words = words
+            .iter()
+            .map(|&word| (word, word.chars().collect::<Vec<_>>()))
+            .filter(|(_, letters)| !letters.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut matches = Vec::new();
+        for start in self.grid.coords() {
+            for direction in Direction::ALL {
+                for (word, letters) in &words {
+                    if self.reads(start, direction, letters) {
+                        matches.push(Match {
+                            word,
+                            start,
+                            direction,
+                        });
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// The top-left corners of every placement of `template`. Rotations are
+    /// not tried; see `Template::rotations`.
+    pub fn find_template(&self, template: &Template) -> Vec<Coord> {
+        let (width, height) = (template.cells.width(), template.cells.height());
+        if self.wrap && (width > self.grid.width() || height > self.grid.height()) {
+            return Vec::new();
+        }
+        let fits = |start: Coord| {
+            template.cells.iter().all(|(at, expected)| {
+                let Some(expected) = expected else {
+                    return true;
+                };
+                self.at(start, at.row as isize, at.col as isize)
+                    .is_some_and(|coord| self.grid[coord] == *expected)
+            })
+        };
+        self.grid.coords().filter(|&start| fits(start)).collect()
+    }
+
+    fn reads(&self, start: Coord, direction: Direction, letters: &[char]) -> bool {
+        if self.wrap && letters.len() > self.cycle(direction) {
+            return false;
+        }
+        let (d_row, d_col) = direction.delta();
+        letters.iter().enumerate().all(|(index, letter)| {
+            let index = index as isize;
+            self.at(start, d_row * index, d_col * index)
+                .is_some_and(|coord| self.grid[coord] == *letter)
+        })
+    }
+
+    fn at(&self, start: Coord, d_row: isize, d_col: isize) -> Option<Coord> {
+        if self.wrap {
+            Some(self.grid.offset_wrapping(start, d_row, d_col))
+        } else {
+            self.grid.offset(start, d_row, d_col)
+        }
+    }
+
+    /// How many steps in `direction` it takes to get back to the start on a
+    /// wrapping grid.
+    fn cycle(&self, direction: Direction) -> usize {
+        match direction.delta() {
+            (0, _) => self.grid.width(),
+            (_, 0) => self.grid.height(),
+            _ => lcm(self.grid.width(), self.grid.height()),
+        }
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    let gcd = |mut a: usize, mut b: usize| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    a / gcd(a, b) * b
+}
+
+/// A small 2D pattern of letters and wildcards, like the X-MAS cross:
+///
+/// ```text
+/// M.S
+/// .A.
+/// M.S
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    cells: Grid<Option<char>>,
+}
+
+impl Template {
+    /// Reads a template one row per line; `wildcard` matches any letter.
+    pub fn parse(text: &str, wildcard: char) -> Result<Self, ParseError> {
+        let cells = Grid::parse(text, |c| Ok((c != wildcard).then_some(c)))?;
+        Ok(Template { cells })
+    }
+
+    /// The distinct quarter turns of this template, itself first.
+    pub fn rotations(&self) -> Vec<Template> {
+        let mut rotations = vec![self.clone()];
+        let mut cells = self.cells.rotate_right();
+        for _ in 0..3 {
+            let next = cells.rotate_right();
+            let template = Template { cells };
+            if !rotations.contains(&template) {
+                rotations.push(template);
+            }
+            cells = next;
+        }
+        rotations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters(text: &str) -> Grid<char> {
+        Grid::parse(text, Ok).unwrap()
+    }
+
+    #[test]
+    fn test_find_reports_start_and_direction() {
+        let grid = letters("XMAS\n.A..\nS.S.");
+        let matches = WordSearch::new(&grid).find(&["XMAS", "SAX"]);
+        assert_eq!(
+            vec![
+                Match {
+                    word: "XMAS",
+                    start: Coord::new(0, 0),
+                    direction: Direction::East,
+                },
+                Match {
+                    word: "SAX",
+                    start: Coord::new(2, 2),
+                    direction: Direction::NorthWest,
+                },
+            ],
+            matches
+        );
+    }
+
+    #[test]
+    fn test_find_both_spellings_on_a_line() {
+        let grid = letters("XMASXXXSAMXA");
+        let search = WordSearch::new(&grid);
+        let directions = search
+            .find(&["XMAS"])
+            .into_iter()
+            .map(|m| m.direction)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Direction::East, Direction::West], directions);
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        let grid = letters("ASXM\n....");
+        assert!(WordSearch::new(&grid).find(&["XMAS"]).is_empty());
+
+        let matches = WordSearch::new(&grid).wrap_around(true).find(&["XMAS"]);
+        assert_eq!(1, matches.len());
+        assert_eq!(Coord::new(0, 2), matches[0].start);
+
+        // Five letters on a row of four would read one cell twice.
+        let matches = WordSearch::new(&grid).wrap_around(true).find(&["SXMAS"]);
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_template_rotations() -> miette::Result<()> {
+        let cross = Template::parse("M.S\n.A.\nM.S", '.')?;
+        assert_eq!(4, cross.rotations().len());
+        let square = Template::parse("AA\nAA", '.')?;
+        assert_eq!(1, square.rotations().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_template() -> miette::Result<()> {
+        let cross = Template::parse("M.S\n.A.\nM.S", '.')?;
+        let grid = letters("MXS\nXAX\nMXS");
+        let search = WordSearch::new(&grid);
+        assert_eq!(vec![Coord::new(0, 0)], search.find_template(&cross));
+        let turned = &cross.rotations()[1];
+        assert!(search.find_template(turned).is_empty());
+
+        let grid = letters("A...\n.S.M\n....\n.S.M");
+        assert!(WordSearch::new(&grid).find_template(&cross).is_empty());
+        let search = WordSearch::new(&grid).wrap_around(true);
+        assert_eq!(vec![Coord::new(3, 3)], search.find_template(&cross));
+        Ok(())
+    }
+}
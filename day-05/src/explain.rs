@@ -0,0 +1,413 @@
+use crate::graph::{Cycle, RuleGraph};
+use crate::{Page, PageList};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// A rule `before|after` that an update breaks by printing `after` first.
+/// Positions are indexes into the update.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub before: Page,
+    pub after: Page,
+    pub before_position: usize,
+    pub after_position: usize,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rule {}|{} broken: {} at position {} is before {} at position {}",
+            self.before,
+            self.after,
+            self.after,
+            self.after_position,
+            self.before,
+            self.before_position
+        )
+    }
+}
+
+/// Takes `page` out of position `from` and puts it back so it ends up at
+/// `to` in the fixed update. Applying an update's moves in order, after
+/// taking all the moved pages out, gives the fixed update.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Move {
+    pub page: Page,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from position {} to position {}",
+            self.page, self.from, self.to
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    /// 1-based, counting updates in the input.
+    pub update: usize,
+    pub pages: PageList,
+    pub violations: Vec<Violation>,
+    pub moves: Vec<Move>,
+    pub fixed: PageList,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "update {}: {}", self.update, join(&self.pages))?;
+        for violation in &self.violations {
+            writeln!(f, "  {}", violation)?;
+        }
+        for step in &self.moves {
+            writeln!(f, "  {}", step)?;
+        }
+        write!(f, "  fixed: {}", join(&self.fixed))
+    }
+}
+
+fn join(pages: &[Page]) -> String {
+    pages
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Explains every update that breaks a rule.
+pub fn explain(graph: &RuleGraph, updates: &[PageList]) -> Result<Vec<Explanation>, Cycle> {
+    updates
+        .iter()
+        .enumerate()
+        .filter(|(_, pages)| !graph.is_valid(pages))
+        .map(|(index, pages)| explain_update(graph, index + 1, pages))
+        .collect()
+}
+
+/// The rules `pages` breaks, and the fewest moves that fix it.
+///
+/// A set of pages can all stay where they are exactly when no rule, direct or
+/// through other pages, puts a later one of them before an earlier one. That
+/// relation is a partial order on positions, so the largest such set is a
+/// maximum antichain, found from a maximum matching as in Dilworth's theorem.
+/// Every other page has to move, and one move each is enough.
+pub fn explain_update(
+    graph: &RuleGraph,
+    update: usize,
+    pages: &[Page],
+) -> Result<Explanation, Cycle> {
+    graph.order(pages)?;
+
+    let len = pages.len();
+    let mut reach = vec![vec![false; len]; len];
+    let mut violations = Vec::new();
+    for after in 0..len {
+        for before in 0..len {
+            reach[before][after] = graph.requires(pages[before], pages[after]);
+            if reach[before][after] && before > after {
+                violations.push(Violation {
+                    before: pages[before],
+                    after: pages[after],
+                    before_position: before,
+                    after_position: after,
+                });
+            }
+        }
+    }
+    for via in 0..len {
+        let through = reach[via].clone();
+        for row in reach.iter_mut().filter(|row| row[via]) {
+            for (cell, &onward) in row.iter_mut().zip(&through) {
+                *cell |= onward;
+            }
+        }
+    }
+
+    let conflicts = |earlier: usize, later: usize| earlier < later && reach[later][earlier];
+    let keep = maximum_antichain(len, conflicts);
+
+    let fixed = order_keeping(&reach, &keep);
+    let mut moves = fixed
+        .iter()
+        .enumerate()
+        .filter(|&(_, &from)| !keep[from])
+        .map(|(to, &from)| Move {
+            page: pages[from],
+            from,
+            to,
+        })
+        .collect::<Vec<_>>();
+    moves.sort_by_key(|step| step.to);
+
+    Ok(Explanation {
+        update,
+        pages: pages.to_vec(),
+        violations,
+        moves,
+        fixed: fixed.into_iter().map(|index| pages[index]).collect(),
+    })
+}
+
+/// Which of `0..len` make up a largest set with no two related by `less`,
+/// a strict partial order. Uses König's theorem on the bipartite graph with
+/// an edge from `a` on the left to `b` on the right whenever `less(a, b)`.
+fn maximum_antichain(len: usize, less: impl Fn(usize, usize) -> bool) -> Vec<bool> {
+    let mut matched_left = vec![None; len];
+    let mut matched_right = vec![None; len];
+    for left in 0..len {
+        let mut visited = vec![false; len];
+        augment(
+            left,
+            &less,
+            &mut visited,
+            &mut matched_left,
+            &mut matched_right,
+        );
+    }
+
+    // Everything reachable by alternating paths from unmatched left vertices.
+    let mut left_reached = vec![false; len];
+    let mut right_reached = vec![false; len];
+    let mut stack = (0..len)
+        .filter(|&left| matched_left[left].is_none())
+        .collect::<Vec<_>>();
+    while let Some(left) = stack.pop() {
+        if std::mem::replace(&mut left_reached[left], true) {
+            continue;
+        }
+        for right in (0..len).filter(|&right| less(left, right)) {
+            if matched_left[left] != Some(right) && !right_reached[right] {
+                right_reached[right] = true;
+                if let Some(next) = matched_right[right] {
+                    stack.push(next);
+                }
+            }
+        }
+    }
+
+    // The minimum vertex cover is the unreached left and the reached right;
+    // the antichain is what it leaves out on both sides.
+    (0..len)
+        .map(|index| left_reached[index] && !right_reached[index])
+        .collect()
+}
+
+fn augment(
+    left: usize,
+    less: &impl Fn(usize, usize) -> bool,
+    visited: &mut [bool],
+    matched_left: &mut [Option<usize>],
+    matched_right: &mut [Option<usize>],
+) -> bool {
+    for right in 0..visited.len() {
+        if !less(left, right) || std::mem::replace(&mut visited[right], true) {
+            continue;
+        }
+        let free = match matched_right[right] {
+            None => true,
+            Some(other) => augment(other, less, visited, matched_left, matched_right),
+        };
+        if free {
+            matched_left[left] = Some(right);
+            matched_right[right] = Some(left);
+            return true;
+        }
+    }
+    false
+}
+
+/// A topological order of positions under `reach` that leaves the kept
+/// positions in their original order, preferring earlier positions.
+fn order_keeping(reach: &[Vec<bool>], keep: &[bool]) -> Vec<usize> {
+    let len = keep.len();
+    let kept = (0..len).filter(|&index| keep[index]).collect::<Vec<_>>();
+    let mut edges = reach.to_vec();
+    for pair in kept.windows(2) {
+        edges[pair[0]][pair[1]] = true;
+    }
+
+    let mut in_degree = vec![0; len];
+    for row in &edges {
+        for (to, &edge) in row.iter().enumerate() {
+            in_degree[to] += usize::from(edge);
+        }
+    }
+    let mut ready = (0..len)
+        .filter(|&index| in_degree[index] == 0)
+        .map(Reverse)
+        .collect::<BinaryHeap<_>>();
+    let mut order = Vec::with_capacity(len);
+    while let Some(Reverse(from)) = ready.pop() {
+        order.push(from);
+        for to in 0..len {
+            if edges[from][to] {
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    ready.push(Reverse(to));
+                }
+            }
+        }
+    }
+    order
+}
+
+pub fn write_json<W: Write>(writer: W, explanations: &[Explanation]) -> io::Result<()> {
+    serde_json::to_writer_pretty(writer, explanations).map_err(io::Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    fn example() -> miette::Result<Vec<Explanation>> {
+        let (graph, updates) = parse(EXAMPLE)?;
+        Ok(explain(&graph, &updates)?)
+    }
+
+    #[test]
+    fn test_explain() -> miette::Result<()> {
+        let explanations = example()?;
+        assert_eq!(
+            vec![4, 5, 6],
+            explanations.iter().map(|e| e.update).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "\
+update 4: 75,97,47,61,53
+  rule 97|75 broken: 75 at position 0 is before 97 at position 1
+  move 75 from position 0 to position 1
+  fixed: 97,75,47,61,53",
+            explanations[0].to_string()
+        );
+
+        let last = &explanations[2];
+        assert_eq!(vec![97, 75, 47, 29, 13], last.fixed);
+        assert_eq!(4, last.violations.len());
+        assert_eq!(2, last.moves.len());
+        Ok(())
+    }
+
+    /// The fewest moves, from every valid order of `pages`: the pages that
+    /// can stay are a longest common subsequence with it.
+    fn brute_force(graph: &RuleGraph, pages: &[Page]) -> usize {
+        fn permutations(pages: &[Page], prefix: &mut PageList, out: &mut Vec<PageList>) {
+            if prefix.len() == pages.len() {
+                out.push(prefix.clone());
+            }
+            for &page in pages {
+                if !prefix.contains(&page) {
+                    prefix.push(page);
+                    permutations(pages, prefix, out);
+                    prefix.pop();
+                }
+            }
+        }
+
+        fn common(a: &[Page], b: &[Page]) -> usize {
+            let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+            for i in 0..a.len() {
+                for j in 0..b.len() {
+                    table[i + 1][j + 1] = if a[i] == b[j] {
+                        table[i][j] + 1
+                    } else {
+                        table[i][j + 1].max(table[i + 1][j])
+                    };
+                }
+            }
+            table[a.len()][b.len()]
+        }
+
+        let mut orders = Vec::new();
+        permutations(pages, &mut Vec::new(), &mut orders);
+        orders
+            .iter()
+            .filter(|order| graph.is_valid(order))
+            .map(|order| pages.len() - common(pages, order))
+            .min()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_moves_are_fewest_under_partial_rules() {
+        // 2 before 6 before 5 before 3 and 7 before 1; 4 is unconstrained.
+        let graph = RuleGraph::new(&[(2, 6), (6, 5), (5, 3), (7, 1)]);
+        for pages in [
+            vec![1, 3, 5, 4, 6, 2, 7],
+            vec![3, 7, 5, 1, 6, 2],
+            vec![5, 3, 4, 2, 6, 1, 7],
+        ] {
+            let explanation = explain_update(&graph, 1, &pages).unwrap();
+            assert!(graph.is_valid(&explanation.fixed));
+            assert_eq!(
+                brute_force(&graph, &pages),
+                explanation.moves.len(),
+                "{:?}",
+                pages
+            );
+
+            let mut applied = pages
+                .iter()
+                .copied()
+                .filter(|page| explanation.moves.iter().all(|step| step.page != *page))
+                .collect::<Vec<_>>();
+            for step in &explanation.moves {
+                applied.insert(step.to, step.page);
+            }
+            assert_eq!(explanation.fixed, applied);
+        }
+    }
+
+    #[test]
+    fn test_write_json() -> miette::Result<()> {
+        let mut out = Vec::new();
+        write_json(&mut out, &example()?[1..2]).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(5, json[0]["update"]);
+        assert_eq!(serde_json::json!([61, 29, 13]), json[0]["fixed"]);
+        assert_eq!(
+            serde_json::json!({"page": 13, "from": 1, "to": 2}),
+            json[0]["moves"][0]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{OrderingRule, Page, PageList};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+
+/// The ordering rules as a dependency graph: an edge from `a` to `b` means
+/// `a` has to be printed before `b`. The rules are kept in a hashed set for
+/// O(1) lookup, and pages that appear in a rule are numbered densely with a
+/// list of the pages each has to come before, so checking an update only
+/// visits the rules about its pages. Both take space in proportion to the
+/// rules, where a matrix over page numbers would grow with their square.
+#[derive(Debug, Clone)]
+pub struct RuleGraph {
+    rules: HashSet<OrderingRule>,
+    index: HashMap<Page, usize>,
+    successors: Vec<Vec<usize>>,
+}
+
+/// Rules that contradict each other. Each page has to come before the next,
+/// and the last before the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub pages: Vec<Page>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the ordering rules contradict each other: ")?;
+        for page in &self.pages {
+            write!(f, "{} before ", page)?;
+        }
+        match self.pages.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+impl miette::Diagnostic for Cycle {}
+
+impl RuleGraph {
+    pub fn new(rules: &[OrderingRule]) -> Self {
+        let mut index = HashMap::new();
+        for &(before, after) in rules {
+            for page in [before, after] {
+                let next = index.len();
+                index.entry(page).or_insert(next);
+            }
+        }
+
+        let mut unique = HashSet::with_capacity(rules.len());
+        let mut successors = vec![Vec::new(); index.len()];
+        for &(before, after) in rules {
+            if unique.insert((before, after)) {
+                successors[index[&before]].push(index[&after]);
+            }
+        }
+        RuleGraph {
+            rules: unique,
+            index,
+            successors,
+        }
+    }
+
+    /// Whether a rule says `before` has to be printed before `after`.
+    pub fn requires(&self, before: Page, after: Page) -> bool {
+        self.rules.contains(&(before, after))
+    }
+
+    /// Whether `pages` break no rule: no page has to come before one already
+    /// printed. Each page's rules are looked up once, so this takes time in
+    /// proportion to the update and the rules about its pages.
+    pub fn is_valid(&self, pages: &[Page]) -> bool {
+        let mut seen = HashSet::with_capacity(pages.len());
+        for page in pages {
+            let Some(&page) = self.index.get(page) else {
+                continue;
+            };
+            if self.successors[page]
+                .iter()
+                .any(|after| seen.contains(after))
+            {
+                return false;
+            }
+            seen.insert(page);
+        }
+        true
+    }
+
+    /// Orders `pages` with Kahn's algorithm, using only the rules between
+    /// pages in the update. Whenever more than one page could come next, the
+    /// one earliest in `pages` does, so pages the rules say nothing about
+    /// keep their order.
+    pub fn order(&self, pages: &[Page]) -> Result<PageList, Cycle> {
+        let edges =
+            |from: usize| (0..pages.len()).filter(move |&to| self.requires(pages[from], pages[to]));
+
+        let mut in_degree = vec![0; pages.len()];
+        for from in 0..pages.len() {
+            for to in edges(from) {
+                in_degree[to] += 1;
+            }
+        }
+
+        let mut ready = (0..pages.len())
+            .filter(|&index| in_degree[index] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(pages.len());
+        while let Some(Reverse(from)) = ready.pop() {
+            order.push(pages[from]);
+            for to in edges(from) {
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    ready.push(Reverse(to));
+                }
+            }
+        }
+
+        if order.len() == pages.len() {
+            Ok(order)
+        } else {
+            Err(self.cycle(pages, &in_degree))
+        }
+    }
+
+    /// Every page Kahn's algorithm could not place still has a rule from
+    /// another such page, so walking those rules backwards has to come round
+    /// to a page it has already seen.
+    fn cycle(&self, pages: &[Page], in_degree: &[usize]) -> Cycle {
+        let stuck = |index: usize| in_degree[index] > 0;
+        let mut path = Vec::new();
+        let mut seen = vec![None; pages.len()];
+        let mut current = (0..pages.len())
+            .find(|&index| stuck(index))
+            .unwrap_or_default();
+        while seen[current].is_none() {
+            seen[current] = Some(path.len());
+            path.push(current);
+            current = (0..pages.len())
+                .find(|&from| stuck(from) && self.requires(pages[from], pages[current]))
+                .unwrap_or(current);
+        }
+
+        let start = seen[current].unwrap_or_default();
+        let pages = path[start..]
+            .iter()
+            .rev()
+            .map(|&index| pages[index])
+            .collect();
+        Cycle { pages }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(rules: &[OrderingRule]) -> RuleGraph {
+        RuleGraph::new(rules)
+    }
+
+    #[test]
+    fn test_order() {
+        let graph = graph(&[(97, 75), (75, 47), (47, 61), (61, 53), (97, 47)]);
+        assert_eq!(
+            Ok(vec![97, 75, 47, 61, 53]),
+            graph.order(&[75, 97, 47, 61, 53])
+        );
+    }
+
+    #[test]
+    fn test_unconstrained_pages_keep_their_order() {
+        let graph = graph(&[(3, 1)]);
+        assert_eq!(Ok(vec![5, 3, 1, 4]), graph.order(&[5, 1, 3, 4]));
+        assert_eq!(Ok(vec![4, 3, 1, 5]), graph.order(&[4, 1, 3, 5]));
+    }
+
+    #[test]
+    fn test_rules_outside_the_update_do_not_apply() {
+        let graph = graph(&[(1, 2), (2, 3), (3, 1)]);
+        assert_eq!(Ok(vec![3, 1]), graph.order(&[1, 3]));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let graph = graph(&[(1, 2), (2, 3), (3, 1), (0, 1)]);
+        let cycle = graph.order(&[0, 3, 2, 1]).unwrap_err();
+        assert_eq!(vec![1, 2, 3], cycle.pages);
+        assert_eq!(
+            "the ordering rules contradict each other: 1 before 2 before 3 before 1",
+            cycle.to_string()
+        );
+    }
+
+    #[test]
+    fn test_is_valid() {
+        let graph = graph(&[(97, 75), (75, 47), (100_000, 97)]);
+        assert!(graph.is_valid(&[100_000, 97, 75, 47]));
+        assert!(graph.is_valid(&[97, 13, 47]));
+        assert!(!graph.is_valid(&[97, 47, 13, 75]));
+        assert!(!graph.is_valid(&[97, 100_000]));
+    }
+
+    #[test]
+    fn test_many_rules() {
+        // A bit matrix over this many pages would take over a gigabyte.
+        let rules = (0..100_000)
+            .map(|page| (page, page + 1))
+            .collect::<Vec<_>>();
+        let graph = graph(&rules);
+        assert!(graph.is_valid(&[5, 6, 99_999, 100_000]));
+        assert!(!graph.is_valid(&[5, 7, 6]));
+        assert!(graph.requires(41, 42) && !graph.requires(42, 41));
+    }
+}
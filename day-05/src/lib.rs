@@ -0,0 +1,98 @@
+pub mod explain;
+pub mod graph;
+pub mod part_1;
+pub mod part_2;
+
+use aoc_core::parse::{line_end, list1, parse_all, IResult};
+use aoc_core::{Day, Solution};
+use graph::RuleGraph;
+use nom::{
+    character::complete::{char, digit1, newline},
+    combinator::{cut, map_res},
+    error::context,
+    multi::{many1, separated_list1},
+    sequence::{separated_pair, terminated},
+};
+
+pub type Page = u32;
+pub type OrderingRule = (Page, Page);
+pub type PageList = Vec<Page>;
+pub type OrderingRules = RuleGraph;
+pub type Manual = (OrderingRules, Vec<PageList>);
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input<'a> = Manual;
+    type Output = usize;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_1::process(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        part_2::process(input)
+    }
+}
+
+pub const DAY: Day = Day::new::<Day05>(5, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<Manual> {
+    let (ordering_rules, page_lists) = parse_all(input, parse_manual)?;
+    Ok((RuleGraph::new(&ordering_rules), page_lists))
+}
+
+fn middle_element(list: &[Page]) -> usize {
+    list[list.len() / 2] as usize
+}
+
+fn parse_manual(input: &str) -> IResult<&str, (Vec<OrderingRule>, Vec<PageList>)> {
+    let (next, ordering_rules) = many1(terminated(ordering_rule, newline))(input)?;
+    let (next, _) = context("a blank line before the updates", newline)(next)?;
+    let (next, page_lists) = separated_list1(newline, page_list)(next)?;
+
+    Ok((next, (ordering_rules, page_lists)))
+}
+
+fn ordering_rule(input: &str) -> IResult<&str, OrderingRule> {
+    terminated(
+        separated_pair(page, cut(context("'|'", char('|'))), cut(page)),
+        line_end("end of the rule"),
+    )(input)
+}
+
+fn page_list(input: &str) -> IResult<&str, PageList> {
+    terminated(list1(char(','), page), line_end("',' or end of the update"))(input)
+}
+
+fn page(input: &str) -> IResult<&str, Page> {
+    context("a page number", map_res(digit1, str::parse))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_core::ParseError;
+
+    #[test]
+    fn test_parse_error_points_at_bad_rule() {
+        let contents = "47|53\n47|5x\n\n75,47\n";
+        let e = parse(contents).unwrap_err();
+        let e = e.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 5), (e.line, e.column));
+        assert_eq!(Some('x'), e.found);
+    }
+
+    #[test]
+    fn test_page_out_of_range() {
+        let contents = "47|53\n\n75,99999999999,53\n";
+        let e = parse(contents).unwrap_err();
+        let e = e.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((3, 4), (e.line, e.column));
+        assert_eq!("a page number", e.expected);
+    }
+}
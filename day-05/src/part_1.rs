@@ -1,103 +1,22 @@
-use nom::{
-    bytes::complete::tag,
-    character::complete::{digit1, newline},
-    multi::{many1, separated_list1},
-    sequence::{separated_pair, terminated},
-    IResult,
-};
-use std::collections::HashMap;
-use std::error::Error;
-use std::fs;
+use crate::{middle_element, Manual};
 
-const FILE_PATH: &str = "./input1.txt";
+pub fn process(manual: &Manual) -> usize {
+    let (ordering_rules_map, page_lists) = manual;
 
-type Page<'a> = &'a str;
-type OrderingRule<'a> = (Page<'a>, Page<'a>);
-type PageList<'a> = Vec<Page<'a>>;
-type OrderingRules<'a> = HashMap<Page<'a>, Vec<Page<'a>>>;
-
-pub fn run() -> Result<String, Box<dyn Error>> {
-    let contents = fs::read_to_string(FILE_PATH)?;
-    match process(&contents) {
-        Ok(result) => Ok(result.to_string()),
-        Err(e) => Err(e.into()),
-    }
-}
-
-fn process(input: &str) -> Result<usize, String> {
-    match parse(input) {
-        Ok((_remaining, results)) => {
-            let (ordering_rules, page_lists) = results;
-            let ordering_rules_map = build_ordering_rules_map(&ordering_rules);
-
-            let sum = page_lists
-                .iter()
-                .filter(|page_list| {
-                    let result = is_ordering_valid(&ordering_rules_map, page_list);
-                    result
-                })
-                .map(|page_list| middle_element(page_list))
-                .sum::<usize>();
-
-            Ok(sum)
-        }
-        Err(_err) => Err("parsing failed".to_string()),
-    }
-}
-
-fn middle_element<'a>(list: &'a Vec<&'a str>) -> usize {
-    let idx = list.len() / 2;
-    list[idx].parse::<usize>().unwrap_or_default()
-}
-
-fn build_ordering_rules_map<'a>(ordering_rules: &'a Vec<OrderingRule<'a>>) -> OrderingRules<'a> {
-    let mut map: OrderingRules<'a> = HashMap::new();
-    for (lower, upper) in ordering_rules {
-        map.entry(upper).or_insert(vec![]).push(lower);
-    }
-    map
-}
-
-fn is_ordering_valid(ordering_rules: &OrderingRules, page_list: &PageList) -> bool {
-    page_list
+    page_lists
         .iter()
-        .enumerate()
-        .fold(true, |acc, (index, &page)| {
-            if !acc {
-                return false;
-            }
-            let next_pages = &page_list[(index + 1)..];
-            let default_lower_pages = vec![];
-            let lower_pages = ordering_rules.get(page).unwrap_or(&default_lower_pages);
-            let is_not_ordered = next_pages
-                .iter()
-                .any(|later_page| lower_pages.contains(later_page));
-            !is_not_ordered
-        })
-}
-
-fn parse(input: &str) -> IResult<&str, (Vec<OrderingRule>, Vec<PageList>)> {
-    let (next, ordering_rules) = many1(terminated(ordering_rule, newline))(input)?;
-    let (next, _) = newline(next)?;
-    let (next, page_lists) = many1(terminated(page_list, newline))(next)?;
-
-    Ok((next, (ordering_rules, page_lists)))
-}
-
-fn ordering_rule(input: &str) -> IResult<&str, OrderingRule> {
-    separated_pair(digit1, tag("|"), digit1)(input)
-}
-
-fn page_list(input: &str) -> IResult<&str, PageList> {
-    separated_list1(tag(","), digit1)(input)
+        .filter(|page_list| ordering_rules_map.is_valid(page_list))
+        .map(|page_list| middle_element(page_list))
+        .sum::<usize>()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse;
 
     #[test]
-    fn test_process() -> Result<(), String> {
+    fn test_process() -> miette::Result<()> {
         let contents = "\
 47|53
 97|13
@@ -129,7 +48,7 @@ mod tests {
 97,13,75,29,47
 ";
 
-        assert_eq!(143, process(contents)?);
+        assert_eq!(143, process(&parse(contents)?));
         Ok(())
     }
 }
@@ -0,0 +1,90 @@
+pub mod part_1;
+pub mod part_2;
+pub mod patrol;
+pub mod playback;
+pub mod sim;
+
+use aoc_core::{Day, Solution};
+use aoc_grid::{Coord, Direction, Grid};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Position {
+    Empty,
+    Obsruction,
+    Guard(Direction),
+    Path,
+    /// Can only be walked onto going this way; from any other side it is an
+    /// obstruction.
+    OneWay(Direction),
+    /// Walking onto one takes the guard to the other with the same label.
+    Teleporter(char),
+}
+
+pub type Lab = Grid<Position>;
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input<'a> = Lab;
+    type Output = usize;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_1::process(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_2::process(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day06>(6, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<Lab> {
+    Ok(Grid::parse(input, parse_lab_space)?)
+}
+
+pub fn find_guard(lab: &Lab) -> Option<(Coord, Direction)> {
+    lab.iter().find_map(|(coord, pos)| match pos {
+        Position::Guard(direction) => Some((coord, *direction)),
+        _ => None,
+    })
+}
+
+const LAB_SPACE: &str = "one of . # ^ v < > X ↑ → ↓ ← or a digit";
+
+fn parse_lab_space(c: char) -> Result<Position, &'static str> {
+    match c {
+        '.' => Ok(Position::Empty),
+        '#' => Ok(Position::Obsruction),
+        '^' => Ok(Position::Guard(Direction::North)),
+        'v' => Ok(Position::Guard(Direction::South)),
+        '<' => Ok(Position::Guard(Direction::West)),
+        '>' => Ok(Position::Guard(Direction::East)),
+        'X' => Ok(Position::Path),
+        '↑' => Ok(Position::OneWay(Direction::North)),
+        '→' => Ok(Position::OneWay(Direction::East)),
+        '↓' => Ok(Position::OneWay(Direction::South)),
+        '←' => Ok(Position::OneWay(Direction::West)),
+        '0'..='9' => Ok(Position::Teleporter(c)),
+        _ => Err(LAB_SPACE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_core::ParseError;
+
+    #[test]
+    fn test_parse_error_points_at_stray_character() {
+        let contents = "..#.\n.^.!\n....\n";
+        let e = parse(contents).unwrap_err();
+        let e = e.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 4), (e.line, e.column));
+        assert_eq!(8, e.offset());
+    }
+}
@@ -0,0 +1,313 @@
+use crate::sim::{Guard, Outcome};
+use crate::{Lab, Position};
+use aoc_grid::{Coord, Direction, Grid};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// Which way a guard turns when it can't go on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Turn {
+    #[default]
+    Right,
+    Left,
+    Around,
+}
+
+impl Turn {
+    pub fn apply(self, facing: Direction) -> Direction {
+        match self {
+            Turn::Right => facing.turn_right(),
+            Turn::Left => facing.turn_left(),
+            Turn::Around => facing.opposite(),
+        }
+    }
+}
+
+/// A teleporter label that is not used exactly twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unpaired {
+    pub label: char,
+    pub positions: Vec<Coord>,
+}
+
+impl fmt::Display for Unpaired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "teleporter {} is on {} cells, it needs exactly 2",
+            self.label,
+            self.positions.len()
+        )
+    }
+}
+
+impl std::error::Error for Unpaired {}
+
+impl miette::Diagnostic for Unpaired {}
+
+/// How a patrol ended, and everywhere the guards went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub outcome: Outcome,
+    /// Ticks until the last guard left, or until the guards were all back
+    /// where they were after an earlier tick.
+    pub ticks: usize,
+    pub visited: Grid<bool>,
+}
+
+/// Walks every guard in a lab a cell at a time, with the tiles the jumping
+/// `Simulator` leaves out: one-way tiles, teleporters, and other guards, who
+/// block the way like obstructions. Guards move in lockstep, one tick each in
+/// reading order, and turn the same way when they can't go on.
+#[derive(Debug, Clone)]
+pub struct Patrol<'a> {
+    lab: &'a Lab,
+    turn: Turn,
+    partners: HashMap<Coord, Coord>,
+    guards: Vec<Guard>,
+}
+
+impl<'a> Patrol<'a> {
+    pub fn new(lab: &'a Lab) -> Result<Self, Unpaired> {
+        let mut guards = Vec::new();
+        let mut teleporters = BTreeMap::<char, Vec<Coord>>::new();
+        for (coord, position) in lab.iter() {
+            match *position {
+                Position::Guard(facing) => guards.push(Guard {
+                    position: coord,
+                    facing,
+                }),
+                Position::Teleporter(label) => teleporters.entry(label).or_default().push(coord),
+                _ => {}
+            }
+        }
+
+        let mut partners = HashMap::new();
+        for (label, positions) in teleporters {
+            let [a, b] = positions[..] else {
+                return Err(Unpaired { label, positions });
+            };
+            partners.insert(a, b);
+            partners.insert(b, a);
+        }
+        Ok(Patrol {
+            lab,
+            turn: Turn::default(),
+            partners,
+            guards,
+        })
+    }
+
+    pub fn with_turn(mut self, turn: Turn) -> Self {
+        self.turn = turn;
+        self
+    }
+
+    pub fn guards(&self) -> &[Guard] {
+        &self.guards
+    }
+
+    /// Where a guard facing `facing` ends up by walking onto `cell`, or `None`
+    /// if it can't.
+    fn enter(
+        &self,
+        cell: Coord,
+        facing: Direction,
+        occupied: impl Fn(Coord) -> bool,
+    ) -> Option<Coord> {
+        match self.lab[cell] {
+            Position::Obsruction => None,
+            Position::OneWay(way) if way != facing => None,
+            _ if occupied(cell) => None,
+            Position::Teleporter(_) => Some(self.partners[&cell]).filter(|&to| !occupied(to)),
+            _ => Some(cell),
+        }
+    }
+
+    /// Moves each guard still in the lab once: a step forward, or a turn if
+    /// the way is blocked. A guard that steps out of the lab becomes `None`.
+    /// Calls `on_enter` for every cell a guard walks or teleports onto.
+    pub fn tick(&self, guards: &mut [Option<Guard>], mut on_enter: impl FnMut(Coord)) {
+        for index in 0..guards.len() {
+            let Some(guard) = guards[index] else {
+                continue;
+            };
+            let Some(ahead) = self.lab.step(guard.position, guard.facing) else {
+                guards[index] = None;
+                continue;
+            };
+
+            let others = &*guards;
+            let occupied = |cell: Coord| {
+                others
+                    .iter()
+                    .enumerate()
+                    .any(|(other, g)| other != index && g.is_some_and(|g| g.position == cell))
+            };
+            let moved = match self.enter(ahead, guard.facing, occupied) {
+                Some(position) => {
+                    on_enter(ahead);
+                    on_enter(position);
+                    Guard { position, ..guard }
+                }
+                None => Guard {
+                    facing: self.turn.apply(guard.facing),
+                    ..guard
+                },
+            };
+            guards[index] = Some(moved);
+        }
+    }
+
+    /// Ticks until every guard has left, or the guards repeat an earlier
+    /// state, which means they go round in a loop for ever.
+    pub fn run(&self) -> Report {
+        let mut visited = Grid::filled(self.lab.width(), self.lab.height(), false);
+        let mut guards = self.guards.iter().copied().map(Some).collect::<Vec<_>>();
+        for guard in &self.guards {
+            visited[guard.position] = true;
+        }
+
+        let mut seen = HashSet::new();
+        let mut ticks = 0;
+        let outcome = loop {
+            if guards.iter().all(Option::is_none) {
+                break Outcome::Exited;
+            }
+            if !seen.insert(guards.clone()) {
+                break Outcome::Looped;
+            }
+            self.tick(&mut guards, |cell| visited[cell] = true);
+            ticks += 1;
+        };
+        Report {
+            outcome,
+            ticks,
+            visited,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    fn visited(report: &Report) -> Vec<(usize, usize)> {
+        report
+            .visited
+            .iter()
+            .filter(|&(_, &visited)| visited)
+            .map(|(coord, _)| (coord.row, coord.col))
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_the_simulator() -> miette::Result<()> {
+        let report = Patrol::new(&parse(EXAMPLE)?)?.run();
+        assert_eq!(Outcome::Exited, report.outcome);
+        assert_eq!(41, visited(&report).len());
+
+        let blocked = EXAMPLE.replacen(".#..^", ".#.#^", 1);
+        let report = Patrol::new(&parse(&blocked)?)?.run();
+        assert_eq!(Outcome::Looped, report.outcome);
+        Ok(())
+    }
+
+    #[test]
+    fn test_turns() -> miette::Result<()> {
+        let lab = parse(".#..\n....\n.^..\n")?;
+        let run = |turn| Patrol::new(&lab).map(|patrol| patrol.with_turn(turn).run());
+        assert_eq!(vec![(1, 0), (1, 1), (2, 1)], visited(&run(Turn::Left)?));
+        assert_eq!(
+            vec![(1, 1), (1, 2), (1, 3), (2, 1)],
+            visited(&run(Turn::Right)?)
+        );
+        assert_eq!(vec![(1, 1), (2, 1)], visited(&run(Turn::Around)?));
+
+        let report = Patrol::new(&parse("#\n^\n#\n")?)?
+            .with_turn(Turn::Around)
+            .run();
+        assert_eq!(Outcome::Looped, report.outcome);
+        Ok(())
+    }
+
+    #[test]
+    fn test_one_way_tiles() -> miette::Result<()> {
+        let report = Patrol::new(&parse(".>→..\n")?)?.run();
+        assert_eq!(vec![(0, 1), (0, 2), (0, 3), (0, 4)], visited(&report));
+
+        // Against the arrow it is a wall, so the guard turns north and leaves.
+        let report = Patrol::new(&parse("..→.<\n")?)?.run();
+        assert_eq!(vec![(0, 3), (0, 4)], visited(&report));
+        assert_eq!(3, report.ticks);
+        Ok(())
+    }
+
+    #[test]
+    fn test_teleporters() -> miette::Result<()> {
+        let report = Patrol::new(&parse(".1..\n....\n.^.1\n")?)?.run();
+        assert_eq!(Outcome::Exited, report.outcome);
+        assert_eq!(
+            vec![(0, 1), (0, 3), (1, 1), (1, 3), (2, 1), (2, 3)],
+            visited(&report)
+        );
+
+        let error = Patrol::new(&parse(".1.\n.^.\n1.1\n")?).unwrap_err();
+        assert_eq!('1', error.label);
+        assert_eq!(
+            "teleporter 1 is on 3 cells, it needs exactly 2",
+            error.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_guards_move_in_lockstep() -> miette::Result<()> {
+        // They meet in the middle, each turns to face out, and both leave.
+        let lab = parse(">..<\n")?;
+        let patrol = Patrol::new(&lab)?;
+        assert_eq!(2, patrol.guards().len());
+        let mut guards = patrol
+            .guards()
+            .iter()
+            .copied()
+            .map(Some)
+            .collect::<Vec<_>>();
+        patrol.tick(&mut guards, |_| {});
+        patrol.tick(&mut guards, |_| {});
+        assert_eq!(
+            vec![
+                Some((Coord::new(0, 1), Direction::South)),
+                Some((Coord::new(0, 2), Direction::North))
+            ],
+            guards
+                .iter()
+                .map(|g| g.map(|g| (g.position, g.facing)))
+                .collect::<Vec<_>>()
+        );
+        let report = patrol.run();
+        assert_eq!((Outcome::Exited, 3), (report.outcome, report.ticks));
+        assert_eq!(4, visited(&report).len());
+
+        // Boxed in, they bounce off the walls and each other for ever.
+        let report = Patrol::new(&parse("#>.<#\n")?)?
+            .with_turn(Turn::Around)
+            .run();
+        assert_eq!(Outcome::Looped, report.outcome);
+        Ok(())
+    }
+}
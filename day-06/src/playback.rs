@@ -0,0 +1,456 @@
+use crate::sim::{Guard, Outcome, Simulator};
+use crate::{Lab, Position};
+use aoc_grid::{Coord, Direction};
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Where an extra obstruction can't go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Misplaced {
+    OutsideLab(Coord),
+    OnGuard(Coord),
+    OnObstruction(Coord),
+}
+
+impl fmt::Display for Misplaced {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Misplaced::OutsideLab(at) => write!(f, "{} is outside the lab", at),
+            Misplaced::OnGuard(at) => write!(f, "{} is where the guard starts", at),
+            Misplaced::OnObstruction(at) => write!(f, "{} is already an obstruction", at),
+        }
+    }
+}
+
+impl std::error::Error for Misplaced {}
+
+impl miette::Diagnostic for Misplaced {}
+
+/// The guard's walk a cell at a time, turns included, so it can be shown
+/// one frame per step.
+#[derive(Debug, Clone)]
+pub struct Playback<'a> {
+    lab: &'a Lab,
+    extra: Option<Coord>,
+    steps: Vec<Guard>,
+    outcome: Outcome,
+    /// Where the loop starts in `steps`, if the guard is in one.
+    loop_start: Option<usize>,
+}
+
+impl<'a> Playback<'a> {
+    /// Fails if `extra` is somewhere an obstruction would change nothing:
+    /// outside the lab, on the guard, or on an obstruction already there.
+    pub fn new(simulator: &Simulator<'a>, extra: Option<Coord>) -> Result<Self, Misplaced> {
+        if let Some(extra) = extra {
+            if !simulator.lab().contains(extra) {
+                return Err(Misplaced::OutsideLab(extra));
+            }
+            if extra == simulator.guard().position {
+                return Err(Misplaced::OnGuard(extra));
+            }
+            if simulator.lab()[extra] == Position::Obsruction {
+                return Err(Misplaced::OnObstruction(extra));
+            }
+        }
+        let mut steps = Vec::new();
+        let outcome = simulator.walk(extra, |segment| {
+            steps.extend(segment.cells().map(|position| Guard {
+                position,
+                facing: segment.facing,
+            }));
+        });
+
+        // The walk stops at the first turn it repeats, so the loop runs from
+        // the first time the guard stood there facing that way.
+        let loop_start = match (outcome, steps.last()) {
+            (Outcome::Looped, Some(last)) => steps.iter().position(|step| step == last),
+            _ => None,
+        };
+        Ok(Playback {
+            lab: simulator.lab(),
+            extra,
+            steps,
+            outcome,
+            loop_start,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    /// The lab after `index` steps. Past the end, the last frame.
+    pub fn frame(&self, index: usize) -> Frame<'_> {
+        Frame {
+            playback: self,
+            index: index.min(self.len().saturating_sub(1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Obstruction,
+    /// The obstruction added for this walk.
+    Extra,
+    Trail,
+    Loop,
+    Guard(Direction),
+}
+
+impl Cell {
+    pub fn symbol(self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::Obstruction => '#',
+            Cell::Extra => 'O',
+            Cell::Trail => 'X',
+            Cell::Loop => '*',
+            Cell::Guard(Direction::North) => '^',
+            Cell::Guard(Direction::East) => '>',
+            Cell::Guard(Direction::South) => 'v',
+            Cell::Guard(Direction::West) => '<',
+            Cell::Guard(_) => '?',
+        }
+    }
+
+    pub fn color(self) -> [u8; 3] {
+        match self {
+            Cell::Empty => [255, 255, 255],
+            Cell::Obstruction => [40, 40, 40],
+            Cell::Extra => [200, 30, 30],
+            Cell::Trail => [150, 180, 255],
+            Cell::Loop => [255, 160, 0],
+            Cell::Guard(_) => [0, 150, 0],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Frame<'p> {
+    playback: &'p Playback<'p>,
+    pub index: usize,
+}
+
+impl Frame<'_> {
+    pub fn guard(&self) -> Option<Guard> {
+        self.playback.steps.get(self.index).copied()
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.index + 1 >= self.playback.len()
+    }
+
+    /// What to draw at each cell. The loop is only marked once the guard has
+    /// closed it, on the last frame.
+    pub fn cells(&self) -> aoc_grid::Grid<Cell> {
+        let playback = self.playback;
+        let mut cells = playback.lab.map(|position| match position {
+            Position::Obsruction => Cell::Obstruction,
+            _ => Cell::Empty,
+        });
+        if let Some(extra) = playback.extra {
+            cells[extra] = Cell::Extra;
+        }
+        let walked = &playback.steps[..(self.index + 1).min(playback.len())];
+        for step in walked {
+            cells[step.position] = Cell::Trail;
+        }
+        if let (Some(start), true) = (playback.loop_start, self.is_last()) {
+            for step in &playback.steps[start..] {
+                cells[step.position] = Cell::Loop;
+            }
+        }
+        if let Some(guard) = self.guard() {
+            cells[guard.position] = Cell::Guard(guard.facing);
+        }
+        cells
+    }
+
+    /// A line saying where the guard is, and how the walk ended on the last
+    /// frame.
+    pub fn status(&self) -> String {
+        let playback = self.playback;
+        let mut status = format!("step {}/{}", self.index + 1, playback.len());
+        if let Some(guard) = self.guard() {
+            status += &format!(", guard at {} facing {:?}", guard.position, guard.facing);
+        }
+        if self.is_last() {
+            match (playback.outcome, playback.loop_start) {
+                (Outcome::Looped, Some(start)) => {
+                    let length = playback.len() - 1 - start;
+                    status += &format!(", looping every {} steps", length);
+                }
+                _ => status += ", leaving the lab",
+            }
+        }
+        status
+    }
+
+    /// Writes the frame as a binary PPM, `scale` pixels to a cell.
+    pub fn write_ppm<W: Write>(&self, mut writer: W, scale: usize) -> io::Result<()> {
+        let cells = self.cells();
+        let scale = scale.max(1);
+        writeln!(
+            writer,
+            "P6\n{} {}\n255",
+            cells.width() * scale,
+            cells.height() * scale
+        )?;
+        for row in cells.rows() {
+            let line = row
+                .iter()
+                .flat_map(|cell| std::iter::repeat_n(cell.color(), scale))
+                .flatten()
+                .collect::<Vec<_>>();
+            for _ in 0..scale {
+                writer.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Frame<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells().rows() {
+            let line = row.iter().map(|cell| cell.symbol()).collect::<String>();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Pause, or carry on if paused.
+    Toggle,
+    /// Pause and show the next frame.
+    Step,
+    Quit,
+}
+
+impl Command {
+    /// Reads a typed line: nothing or `p` to pause and resume, `s` or `n` to
+    /// step, `q` to quit.
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "" | "p" => Some(Command::Toggle),
+            "s" | "n" => Some(Command::Step),
+            "q" => Some(Command::Quit),
+            _ => None,
+        }
+    }
+}
+
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// Animates `playback` on `out`, one frame every `speed`, taking commands as
+/// they arrive. Stops on the last frame, on `Command::Quit`, or once
+/// `commands` is closed while paused. Closed while playing, it carries on at
+/// the same speed without commands.
+pub fn play<W: Write>(
+    playback: &Playback,
+    speed: Duration,
+    commands: &Receiver<Command>,
+    mut out: W,
+) -> io::Result<()> {
+    let mut index = 0;
+    let mut paused = false;
+    let mut closed = false;
+    loop {
+        let frame = playback.frame(index);
+        let state = if paused { "  [paused]" } else { "" };
+        writeln!(out, "{}{}{}{}", CLEAR, frame, frame.status(), state)?;
+        out.flush()?;
+        if frame.is_last() {
+            return Ok(());
+        }
+
+        let command = if paused {
+            match commands.recv() {
+                Ok(command) => Some(command),
+                Err(_) => return Ok(()),
+            }
+        } else if closed {
+            thread::sleep(speed);
+            None
+        } else {
+            match commands.recv_timeout(speed) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                // Nothing will wait out the rest of the frame any more.
+                Err(RecvTimeoutError::Disconnected) => {
+                    closed = true;
+                    thread::sleep(speed);
+                    None
+                }
+            }
+        };
+        match command {
+            None => index += 1,
+            Some(Command::Toggle) => paused = !paused,
+            Some(Command::Step) => {
+                paused = true;
+                index += 1;
+            }
+            Some(Command::Quit) => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use std::sync::mpsc;
+
+    const LAB: &str = "\
+.#...
+....#
+.^...
+...#.
+";
+
+    fn playback(lab: &Lab, extra: Option<Coord>) -> Playback<'_> {
+        Playback::new(&Simulator::new(lab).unwrap(), extra).unwrap()
+    }
+
+    #[test]
+    fn test_frames() -> miette::Result<()> {
+        let lab = parse(LAB)?;
+        let playback = playback(&lab, None);
+        assert_eq!(Outcome::Exited, playback.outcome());
+        assert_eq!(
+            ".#...\n....#\n.^...\n...#.\n",
+            playback.frame(0).to_string()
+        );
+        assert_eq!(
+            ".#...\n.>..#\n.X...\n...#.\n",
+            playback.frame(2).to_string()
+        );
+        assert_eq!(
+            ".#...\n.XXX#\n<XXX.\n...#.\n",
+            playback.frame(playback.len()).to_string()
+        );
+        assert_eq!(
+            "step 11/11, guard at (2, 0) facing West, leaving the lab",
+            playback.frame(10).status()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_loop_is_marked_on_last_frame() -> miette::Result<()> {
+        let lab = parse(LAB)?;
+        let playback = playback(&lab, Some(Coord::new(2, 0)));
+        assert_eq!(Outcome::Looped, playback.outcome());
+        let last = playback.frame(playback.len());
+        assert_eq!(".#...\n.^**#\nO***.\n...#.\n", last.to_string());
+        assert!(last.status().ends_with("looping every 10 steps"));
+        assert!(!playback.frame(3).to_string().contains('*'));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extra_obstruction_outside_the_lab() -> miette::Result<()> {
+        let lab = parse(LAB)?;
+        let simulator = Simulator::new(&lab).unwrap();
+        assert_eq!(
+            Misplaced::OutsideLab(Coord::new(4, 0)),
+            Playback::new(&simulator, Some(Coord::new(4, 0))).unwrap_err()
+        );
+        assert!(Playback::new(&simulator, Some(Coord::new(0, 5))).is_err());
+        assert!(Playback::new(&simulator, Some(Coord::new(3, 4))).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extra_obstruction_on_the_guard() -> miette::Result<()> {
+        let lab = parse(LAB)?;
+        let simulator = Simulator::new(&lab).unwrap();
+        let error = Playback::new(&simulator, Some(Coord::new(2, 1))).unwrap_err();
+        assert_eq!(Misplaced::OnGuard(Coord::new(2, 1)), error);
+        assert_eq!("(2, 1) is where the guard starts", error.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extra_obstruction_on_an_obstruction() -> miette::Result<()> {
+        let lab = parse(LAB)?;
+        let simulator = Simulator::new(&lab).unwrap();
+        let error = Playback::new(&simulator, Some(Coord::new(1, 4))).unwrap_err();
+        assert_eq!(Misplaced::OnObstruction(Coord::new(1, 4)), error);
+        assert_eq!("(1, 4) is already an obstruction", error.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_ppm() -> miette::Result<()> {
+        let lab = parse(LAB)?;
+        let mut out = Vec::new();
+        playback(&lab, None)
+            .frame(0)
+            .write_ppm(&mut out, 2)
+            .unwrap();
+        let header = b"P6\n10 8\n255\n";
+        assert_eq!(header, &out[..header.len()]);
+        assert_eq!(header.len() + 10 * 8 * 3, out.len());
+        let pixel = |x: usize, y: usize| {
+            let start = header.len() + (y * 10 + x) * 3;
+            [out[start], out[start + 1], out[start + 2]]
+        };
+        assert_eq!(Cell::Obstruction.color(), pixel(3, 1));
+        assert_eq!(Cell::Guard(Direction::North).color(), pixel(2, 5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_play() -> miette::Result<()> {
+        let lab = parse(LAB)?;
+        let playback = playback(&lab, None);
+
+        let (sender, receiver) = mpsc::channel();
+        for command in [Command::Step, Command::Step, Command::Quit] {
+            sender.send(command).unwrap();
+        }
+        let mut out = Vec::new();
+        play(&playback, Duration::from_secs(60), &receiver, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(3, out.matches(CLEAR).count());
+        assert!(out.ends_with("step 3/11, guard at (1, 1) facing East  [paused]\n"));
+
+        // With stdin at its end the sender is gone, but frames are still
+        // paced.
+        let (sender, receiver) = mpsc::channel();
+        drop(sender);
+        let speed = Duration::from_millis(20);
+        let mut out = Vec::new();
+        let start = std::time::Instant::now();
+        play(&playback, speed, &receiver, &mut out).unwrap();
+        assert!(start.elapsed() >= speed * 10);
+        assert_eq!(11, String::from_utf8(out).unwrap().matches(CLEAR).count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(Some(Command::Toggle), Command::parse("\n"));
+        assert_eq!(Some(Command::Step), Command::parse("n"));
+        assert_eq!(Some(Command::Quit), Command::parse(" q "));
+        assert_eq!(None, Command::parse("x"));
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{find_guard, Lab, Position};
+use aoc_core::BitSet;
+use aoc_grid::{Coord, Direction, Grid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Guard {
+    pub position: Coord,
+    pub facing: Direction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Exited,
+    Looped,
+}
+
+/// One straight stretch of the guard's walk, from `start` to `end` inclusive.
+/// `end` is either in front of an obstruction or at the edge of the lab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub start: Coord,
+    pub end: Coord,
+    pub facing: Direction,
+}
+
+impl Segment {
+    pub fn cells(&self) -> impl Iterator<Item = Coord> + '_ {
+        std::iter::successors(Some(self.start), |&cell| {
+            (cell != self.end).then(|| cell.step(self.facing)).flatten()
+        })
+    }
+}
+
+fn direction_index(direction: Direction) -> usize {
+    Direction::ORTHOGONAL
+        .iter()
+        .position(|&d| d == direction)
+        .expect("the guard only faces north, east, south or west")
+}
+
+/// Walks the guard through a lab that never changes. Rather than moving a
+/// cell at a time, the guard jumps straight to the next obstruction in its
+/// row or column, found from sorted indexes of where the obstructions are.
+/// An extra obstruction can be dropped in for a single walk without
+/// touching the lab. Only obstructions are taken into account; one-way tiles,
+/// teleporters and other guards need a `Patrol`.
+#[derive(Debug, Clone)]
+pub struct Simulator<'a> {
+    lab: &'a Lab,
+    guard: Guard,
+    /// Columns of the obstructions in each row, in order.
+    rows: Vec<Vec<usize>>,
+    /// Rows of the obstructions in each column, in order.
+    columns: Vec<Vec<usize>>,
+}
+
+impl<'a> Simulator<'a> {
+    /// `None` if the lab has no guard in it.
+    pub fn new(lab: &'a Lab) -> Option<Self> {
+        let (position, facing) = find_guard(lab)?;
+        let mut rows = vec![Vec::new(); lab.height()];
+        let mut columns = vec![Vec::new(); lab.width()];
+        for (coord, position) in lab.iter() {
+            if *position == Position::Obsruction {
+                rows[coord.row].push(coord.col);
+                columns[coord.col].push(coord.row);
+            }
+        }
+        Some(Simulator {
+            lab,
+            guard: Guard { position, facing },
+            rows,
+            columns,
+        })
+    }
+
+    pub fn lab(&self) -> &'a Lab {
+        self.lab
+    }
+
+    pub fn guard(&self) -> Guard {
+        self.guard
+    }
+
+    /// The first obstruction the guard runs into, if any.
+    fn next_obstruction(&self, guard: Guard, extra: Option<Coord>) -> Option<Coord> {
+        let Coord { row, col } = guard.position;
+        let found = match guard.facing {
+            Direction::North => before(&self.columns[col], row).map(|r| Coord::new(r, col)),
+            Direction::South => after(&self.columns[col], row).map(|r| Coord::new(r, col)),
+            Direction::West => before(&self.rows[row], col).map(|c| Coord::new(row, c)),
+            Direction::East => after(&self.rows[row], col).map(|c| Coord::new(row, c)),
+            _ => None,
+        };
+
+        let Some(extra) = extra.filter(|&extra| ahead(guard, extra)) else {
+            return found;
+        };
+        let distance = |coord: Coord| coord.row.abs_diff(row) + coord.col.abs_diff(col);
+        match found {
+            Some(found) if distance(found) < distance(extra) => Some(found),
+            _ => Some(extra),
+        }
+    }
+
+    /// How far `guard` walks before turning or leaving.
+    fn segment(&self, guard: Guard, extra: Option<Coord>) -> (Segment, Option<Guard>) {
+        let (end, next) = match self.next_obstruction(guard, extra) {
+            Some(obstruction) => {
+                let end = obstruction
+                    .step(guard.facing.opposite())
+                    .expect("the guard is between itself and the obstruction");
+                let next = Guard {
+                    position: end,
+                    facing: guard.facing.turn_right(),
+                };
+                (end, Some(next))
+            }
+            None => (self.edge(guard), None),
+        };
+        let segment = Segment {
+            start: guard.position,
+            end,
+            facing: guard.facing,
+        };
+        (segment, next)
+    }
+
+    fn edge(&self, guard: Guard) -> Coord {
+        let Coord { row, col } = guard.position;
+        match guard.facing {
+            Direction::North => Coord::new(0, col),
+            Direction::South => Coord::new(self.lab.height() - 1, col),
+            Direction::West => Coord::new(row, 0),
+            _ => Coord::new(row, self.lab.width() - 1),
+        }
+    }
+
+    /// Room to record where the guard has turned, for `walk_with`. One set
+    /// can be reused for any number of walks.
+    pub fn turns(&self) -> BitSet {
+        BitSet::new(self.lab.width() * self.lab.height() * 4)
+    }
+
+    /// Walks until the guard leaves the lab or turns somewhere it has already
+    /// turned facing the same way, which means it is in a loop. Calls
+    /// `on_segment` for every stretch walked.
+    pub fn walk(&self, extra: Option<Coord>, on_segment: impl FnMut(Segment)) -> Outcome {
+        self.walk_with(extra, &mut self.turns(), on_segment)
+    }
+
+    /// `walk`, recording turns in `turned`, which comes from `turns` and is
+    /// cleared first.
+    pub fn walk_with(
+        &self,
+        extra: Option<Coord>,
+        turned: &mut BitSet,
+        mut on_segment: impl FnMut(Segment),
+    ) -> Outcome {
+        turned.clear();
+        let mut guard = self.guard;
+        loop {
+            let (segment, next) = self.segment(guard, extra);
+            on_segment(segment);
+            let Some(next) = next else {
+                return Outcome::Exited;
+            };
+            let index = (segment.end.row * self.lab.width() + segment.end.col) * 4
+                + direction_index(segment.facing);
+            if !turned.insert(index) {
+                return Outcome::Looped;
+            }
+            guard = next;
+        }
+    }
+
+    pub fn outcome(&self, extra: Option<Coord>) -> Outcome {
+        self.walk(extra, |_| {})
+    }
+
+    pub fn outcome_with(&self, extra: Option<Coord>, turned: &mut BitSet) -> Outcome {
+        self.walk_with(extra, turned, |_| {})
+    }
+
+    /// Every cell the guard stands on, and how the walk ends.
+    pub fn visited(&self, extra: Option<Coord>) -> (Grid<bool>, Outcome) {
+        let mut visited = Grid::filled(self.lab.width(), self.lab.height(), false);
+        let outcome = self.walk(extra, |segment| {
+            for cell in segment.cells() {
+                visited[cell] = true;
+            }
+        });
+        (visited, outcome)
+    }
+}
+
+fn ahead(guard: Guard, coord: Coord) -> bool {
+    let Coord { row, col } = guard.position;
+    match guard.facing {
+        Direction::North => coord.col == col && coord.row < row,
+        Direction::South => coord.col == col && coord.row > row,
+        Direction::West => coord.row == row && coord.col < col,
+        Direction::East => coord.row == row && coord.col > col,
+        _ => false,
+    }
+}
+
+/// The largest of `sorted` below `value`.
+fn before(sorted: &[usize], value: usize) -> Option<usize> {
+    let index = sorted.partition_point(|&x| x < value);
+    index.checked_sub(1).map(|index| sorted[index])
+}
+
+/// The smallest of `sorted` above `value`.
+fn after(sorted: &[usize], value: usize) -> Option<usize> {
+    let index = sorted.partition_point(|&x| x <= value);
+    sorted.get(index).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    #[test]
+    fn test_segments() -> miette::Result<()> {
+        let lab = parse(EXAMPLE)?;
+        let simulator = Simulator::new(&lab).unwrap();
+        let mut segments = Vec::new();
+        simulator.walk(None, |segment| segments.push(segment));
+        let ends = segments
+            .iter()
+            .take(3)
+            .map(|s| (s.end.row, s.end.col, s.facing))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (1, 4, Direction::North),
+                (1, 8, Direction::East),
+                (6, 8, Direction::South)
+            ],
+            ends
+        );
+        let last = segments.last().unwrap();
+        assert_eq!(
+            (Coord::new(9, 7), Direction::South),
+            (last.end, last.facing)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_visited() -> miette::Result<()> {
+        let lab = parse(EXAMPLE)?;
+        let (visited, outcome) = Simulator::new(&lab).unwrap().visited(None);
+        assert_eq!(Outcome::Exited, outcome);
+        assert_eq!(41, visited.values().filter(|&&v| v).count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extra_obstruction() -> miette::Result<()> {
+        let lab = parse(EXAMPLE)?;
+        let simulator = Simulator::new(&lab).unwrap();
+        assert_eq!(Outcome::Looped, simulator.outcome(Some(Coord::new(6, 3))));
+        assert_eq!(Outcome::Exited, simulator.outcome(Some(Coord::new(0, 0))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_reused_turns() -> miette::Result<()> {
+        let lab = parse(EXAMPLE)?;
+        let simulator = Simulator::new(&lab).unwrap();
+        let mut turned = simulator.turns();
+        for (extra, outcome) in [
+            (Coord::new(6, 3), Outcome::Looped),
+            (Coord::new(0, 0), Outcome::Exited),
+            (Coord::new(7, 6), Outcome::Looped),
+            (Coord::new(6, 3), Outcome::Looped),
+        ] {
+            assert_eq!(outcome, simulator.outcome_with(Some(extra), &mut turned));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_immediate_turns() -> miette::Result<()> {
+        // Boxed in on three sides, the guard turns twice on the spot and
+        // leaves the way it came.
+        let lab = parse(".#.\n#^#\n...\n")?;
+        let (visited, outcome) = Simulator::new(&lab).unwrap().visited(None);
+        assert_eq!(Outcome::Exited, outcome);
+        assert_eq!(2, visited.values().filter(|&&v| v).count());
+
+        let lab = parse(".#.\n#^#\n.#.\n")?;
+        assert_eq!(Outcome::Looped, Simulator::new(&lab).unwrap().outcome(None));
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+pub mod operator;
+pub mod part_1;
+pub mod part_2;
+pub mod solve;
+
+use aoc_core::parse::{line_end, list1, parse_all, IResult};
+use aoc_core::{Day, Solution};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{digit1, newline, space1},
+    combinator::{cut, map_res},
+    error::context,
+    multi::separated_list1,
+    sequence::terminated,
+};
+
+pub type Equation = (u64, Vec<u64>);
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input<'a> = Vec<Equation>;
+    type Output = u64;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_1::process(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_2::process(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day07>(7, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<Vec<Equation>> {
+    Ok(parse_all(input, parse_equations)?)
+}
+
+fn parse_equations(input: &str) -> IResult<&str, Vec<Equation>> {
+    separated_list1(
+        newline,
+        terminated(equation, line_end("a number or end of line")),
+    )(input)
+}
+
+fn equation(input: &str) -> IResult<&str, Equation> {
+    let (next, result_value) = parse_number(input)?;
+    let (next, _) = cut(context("\": \"", tag(": ")))(next)?;
+    let (input, inputs) = cut(list1(space1, parse_number))(next)?;
+    let result = (result_value, inputs);
+    Ok((input, result))
+}
+
+fn parse_number(input: &str) -> IResult<&str, u64> {
+    context("a number", map_res(digit1, str::parse))(input)
+}
@@ -0,0 +1,249 @@
+/// What the left operand has to be for `left op right` to come to a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Left {
+    /// No left operand works.
+    None,
+    Exactly(u64),
+    /// Anything from the first to the second, inclusive, as when dividing
+    /// rounds down.
+    Between(u64, u64),
+    /// Every left operand works, as when multiplying by zero to get zero.
+    Any,
+}
+
+impl Left {
+    fn exactly(left: Option<u64>) -> Self {
+        left.map_or(Left::None, Left::Exactly)
+    }
+}
+
+/// A binary operator on `u64`s. Expressions are evaluated left to right, and
+/// one is only valid if every step along the way is defined and fits.
+pub trait Operator {
+    /// How it is written between its operands.
+    fn symbol(&self) -> &str;
+
+    /// `left op right`, or `None` if that isn't defined or doesn't fit in a
+    /// `u64`.
+    fn apply(&self, left: u64, right: u64) -> Option<u64>;
+
+    /// Works `left op right = target` back to `left`. Every left operand this
+    /// allows has to give `target`, so the solver can prune on it.
+    fn undo(&self, target: u64, right: u64) -> Left;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Add;
+
+impl Operator for Add {
+    fn symbol(&self) -> &str {
+        "+"
+    }
+
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        left.checked_add(right)
+    }
+
+    fn undo(&self, target: u64, right: u64) -> Left {
+        Left::exactly(target.checked_sub(right))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mult;
+
+impl Operator for Mult {
+    fn symbol(&self) -> &str {
+        "*"
+    }
+
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        left.checked_mul(right)
+    }
+
+    fn undo(&self, target: u64, right: u64) -> Left {
+        match (target, right) {
+            (0, 0) => Left::Any,
+            (_, 0) => Left::None,
+            _ => Left::exactly(target.is_multiple_of(right).then(|| target / right)),
+        }
+    }
+}
+
+/// Writes the digits of `right` after those of `left`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Concat;
+
+impl Operator for Concat {
+    fn symbol(&self) -> &str {
+        "||"
+    }
+
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        left.checked_mul(shift(right)?)?.checked_add(right)
+    }
+
+    fn undo(&self, target: u64, right: u64) -> Left {
+        let Some(rest) = target.checked_sub(right) else {
+            return Left::None;
+        };
+        match shift(right) {
+            Some(shift) => Left::exactly(rest.is_multiple_of(shift).then(|| rest / shift)),
+            // Only zero shifted past the top of a u64 stays in range.
+            None => Left::exactly((rest == 0).then_some(0)),
+        }
+    }
+}
+
+/// The power of ten `right` is shifted up by when concatenated, or `None` if
+/// it doesn't fit in a `u64`.
+fn shift(right: u64) -> Option<u64> {
+    10u64.checked_pow(right.checked_ilog10().unwrap_or(0) + 1)
+}
+
+/// Subtraction, only defined while it stays non-negative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sub;
+
+impl Operator for Sub {
+    fn symbol(&self) -> &str {
+        "-"
+    }
+
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        left.checked_sub(right)
+    }
+
+    fn undo(&self, target: u64, right: u64) -> Left {
+        Left::exactly(target.checked_add(right))
+    }
+}
+
+/// Integer division, rounding down. Dividing by zero is not defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Div;
+
+impl Operator for Div {
+    fn symbol(&self) -> &str {
+        "/"
+    }
+
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        left.checked_div(right)
+    }
+
+    fn undo(&self, target: u64, right: u64) -> Left {
+        match (right, target.checked_mul(right)) {
+            (0, _) | (_, None) => Left::None,
+            (_, Some(low)) => Left::Between(low, low.saturating_add(right - 1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pow;
+
+impl Operator for Pow {
+    fn symbol(&self) -> &str {
+        "**"
+    }
+
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        match left {
+            0 | 1 if right > 0 => Some(left),
+            _ => left.checked_pow(u32::try_from(right).ok()?),
+        }
+    }
+
+    /// The `right`th root of `target`, if it is a whole number, found by
+    /// binary search since powers only grow with the base.
+    fn undo(&self, target: u64, right: u64) -> Left {
+        if right == 0 {
+            return if target == 1 { Left::Any } else { Left::None };
+        }
+        let (mut low, mut high) = (0, target);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.apply(middle, right) {
+                Some(value) if value < target => low = middle + 1,
+                _ => high = middle,
+            }
+        }
+        Left::exactly((self.apply(low, right) == Some(target)).then_some(low))
+    }
+}
+
+/// Bitwise exclusive or.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xor;
+
+impl Operator for Xor {
+    fn symbol(&self) -> &str {
+        "^"
+    }
+
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        Some(left ^ right)
+    }
+
+    fn undo(&self, target: u64, right: u64) -> Left {
+        Left::Exactly(target ^ right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat() {
+        assert_eq!(Some(156), Concat.apply(15, 6));
+        assert_eq!(Some(120), Concat.apply(12, 0));
+        assert_eq!(Some(12345), Concat.apply(12, 345));
+        assert_eq!(None, Concat.apply(u64::MAX / 10, 99));
+        assert_eq!(Left::Exactly(15), Concat.undo(156, 6));
+        assert_eq!(Left::Exactly(12), Concat.undo(120, 0));
+        assert_eq!(Left::Exactly(12), Concat.undo(1256, 56));
+        assert_eq!(Left::None, Concat.undo(156, 7));
+        assert_eq!(Left::None, Concat.undo(5, 6));
+        assert_eq!(Left::Exactly(0), Concat.undo(u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn test_undo_gives_back_the_target() {
+        let operators: [&dyn Operator; 7] = [&Add, &Mult, &Concat, &Sub, &Div, &Pow, &Xor];
+        for operator in operators {
+            for target in 0..200 {
+                for right in 0..12 {
+                    let lefts = match operator.undo(target, right) {
+                        Left::None => continue,
+                        Left::Exactly(left) => left..=left,
+                        Left::Between(low, high) => low..=high,
+                        Left::Any => 0..=200,
+                    };
+                    for left in lefts {
+                        assert_eq!(
+                            Some(target),
+                            operator.apply(left, right),
+                            "{} {} {}",
+                            left,
+                            operator.symbol(),
+                            right
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(Left::Exactly(3), Pow.undo(81, 4));
+        assert_eq!(Left::None, Pow.undo(80, 4));
+        assert_eq!(Left::Exactly(1 << 31), Pow.undo(1 << 62, 2));
+        assert_eq!(Left::Exactly(u64::MAX), Pow.undo(u64::MAX, 1));
+        assert_eq!(Left::Exactly(0), Pow.undo(0, 100));
+        assert_eq!(Left::Any, Pow.undo(1, 0));
+        assert_eq!(None, Pow.apply(2, 64));
+    }
+}
@@ -0,0 +1,352 @@
+use crate::operator::{Add, Concat, Left, Mult, Operator};
+use crate::Equation;
+use std::fmt;
+
+/// Operands with the operators found between them.
+#[derive(Clone)]
+pub struct Expression<'s> {
+    pub operands: Vec<u64>,
+    pub operators: Vec<&'s dyn Operator>,
+}
+
+impl Expression<'_> {
+    /// The value, evaluating left to right, or `None` if a step isn't
+    /// defined.
+    pub fn value(&self) -> Option<u64> {
+        let (&first, rest) = self.operands.split_first()?;
+        rest.iter()
+            .zip(&self.operators)
+            .try_fold(first, |left, (&right, operator)| {
+                operator.apply(left, right)
+            })
+    }
+}
+
+impl fmt::Display for Expression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut operands = self.operands.iter();
+        if let Some(first) = operands.next() {
+            write!(f, "{}", first)?;
+        }
+        for (operand, operator) in operands.zip(&self.operators) {
+            write!(f, " {} {}", operator.symbol(), operand)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Expression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Expression({})", self)
+    }
+}
+
+/// Fills in operators between operands to reach a target, working backwards
+/// from the last operand. Each operator only has to be tried where it can be
+/// undone: subtracting an addition has to stay non-negative, dividing out a
+/// multiplication has to be exact, and stripping a concatenation needs the
+/// target to end in the operand's digits. Where a wide range of left operands
+/// would do, as after a division, the operands before are evaluated forwards
+/// instead when that is fewer steps.
+#[derive(Default)]
+pub struct Solver {
+    operators: Vec<Box<dyn Operator>>,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Part 1: `+` and `*`.
+    pub fn part_1() -> Self {
+        Self::new().register(Add).register(Mult)
+    }
+
+    /// Part 2: `+`, `*` and `||`.
+    pub fn part_2() -> Self {
+        Self::part_1().register(Concat)
+    }
+
+    /// Adds an operator, tried after the ones already registered.
+    pub fn register(mut self, operator: impl Operator + 'static) -> Self {
+        self.operators.push(Box::new(operator));
+        self
+    }
+
+    pub fn operators(&self) -> impl Iterator<Item = &dyn Operator> {
+        self.operators.iter().map(|operator| &**operator)
+    }
+
+    pub fn solvable(&self, target: u64, operands: &[u64]) -> bool {
+        self.solve(target, operands).is_some()
+    }
+
+    /// The first expression over `operands` that comes to `target`, trying
+    /// operators in the order they were registered from the right.
+    pub fn solve(&self, target: u64, operands: &[u64]) -> Option<Expression<'_>> {
+        let mut chosen = vec![None; operands.len().saturating_sub(1)];
+        if !self.find(target, operands, &mut chosen) {
+            return None;
+        }
+        Some(Expression {
+            operands: operands.to_vec(),
+            operators: chosen.into_iter().flatten().collect(),
+        })
+    }
+
+    fn find<'s>(
+        &'s self,
+        target: u64,
+        operands: &[u64],
+        chosen: &mut [Option<&'s dyn Operator>],
+    ) -> bool {
+        let [rest @ .., last] = operands else {
+            return false;
+        };
+        if rest.is_empty() {
+            return *last == target;
+        }
+        self.operators().any(|operator| {
+            chosen[rest.len() - 1] = Some(operator);
+            match operator.undo(target, *last) {
+                Left::None => false,
+                Left::Exactly(left) => self.find(left, rest, chosen),
+                Left::Between(low, high) if !self.forwards_is_cheaper(low, high, rest) => {
+                    (low..=high).any(|left| self.find(left, rest, chosen))
+                }
+                Left::Between(low, high) => {
+                    let prefix = &mut chosen[..rest.len() - 1];
+                    self.find_within(rest[0], &rest[1..], (low, high), prefix)
+                }
+                Left::Any => {
+                    let prefix = &mut chosen[..rest.len() - 1];
+                    self.find_within(rest[0], &rest[1..], (0, u64::MAX), prefix)
+                }
+            }
+        })
+    }
+
+    /// Whether evaluating every expression over `operands` forwards takes
+    /// fewer steps than searching backwards from each of `low..=high`.
+    fn forwards_is_cheaper(&self, low: u64, high: u64, operands: &[u64]) -> bool {
+        let operators = u64::try_from(self.operators.len()).unwrap_or(u64::MAX);
+        let exponent = u32::try_from(operands.len() - 1).unwrap_or(u32::MAX);
+        operators.saturating_pow(exponent) <= high - low
+    }
+
+    /// Fills in `chosen`, one operator for each of `rest`, with any expression
+    /// over `first` and `rest` that is defined and comes to something in
+    /// `low..=high`, evaluating forwards.
+    fn find_within<'s>(
+        &'s self,
+        first: u64,
+        rest: &[u64],
+        (low, high): (u64, u64),
+        chosen: &mut [Option<&'s dyn Operator>],
+    ) -> bool {
+        let Some((&next, after)) = rest.split_first() else {
+            return (low..=high).contains(&first);
+        };
+        let index = chosen.len() - rest.len();
+        self.operators().any(|operator| {
+            chosen[index] = Some(operator);
+            operator
+                .apply(first, next)
+                .is_some_and(|value| self.find_within(value, after, (low, high), chosen))
+        })
+    }
+
+    /// How many ways of filling in the operators come to `target`.
+    pub fn count(&self, target: u64, operands: &[u64]) -> u64 {
+        let [rest @ .., last] = operands else {
+            return 0;
+        };
+        if rest.is_empty() {
+            return u64::from(*last == target);
+        }
+        self.operators()
+            .map(|operator| match operator.undo(target, *last) {
+                Left::None => 0,
+                Left::Exactly(left) => self.count(left, rest),
+                Left::Between(low, high) if !self.forwards_is_cheaper(low, high, rest) => {
+                    (low..=high).map(|left| self.count(left, rest)).sum()
+                }
+                Left::Between(low, high) => self.count_within(rest[0], &rest[1..], (low, high)),
+                Left::Any => self.count_within(rest[0], &rest[1..], (0, u64::MAX)),
+            })
+            .sum()
+    }
+
+    fn count_within(&self, first: u64, rest: &[u64], (low, high): (u64, u64)) -> u64 {
+        let Some((&next, rest)) = rest.split_first() else {
+            return u64::from((low..=high).contains(&first));
+        };
+        self.operators()
+            .filter_map(|operator| operator.apply(first, next))
+            .map(|value| self.count_within(value, rest, (low, high)))
+            .sum()
+    }
+
+    /// The sum of the targets of the equations that can be made true.
+    pub fn calibration(&self, equations: &[Equation]) -> u64 {
+        equations
+            .iter()
+            .filter(|(target, operands)| self.solvable(*target, operands))
+            .map(|(target, _)| target)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::{Div, Pow, Sub, Xor};
+    use crate::parse;
+
+    #[test]
+    fn test_expressions() -> miette::Result<()> {
+        let contents = "\
+190: 10 19
+3267: 81 40 27
+83: 17 5
+292: 11 6 16 20
+7290: 6 8 6 15
+";
+        let equations = parse(contents)?;
+        let solver = Solver::part_2();
+        let found = equations
+            .iter()
+            .map(|(target, operands)| solver.solve(*target, operands).map(|e| e.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Some("10 * 19".to_string()),
+                Some("81 * 40 + 27".to_string()),
+                None,
+                Some("11 + 6 * 16 + 20".to_string()),
+                Some("6 * 8 || 6 * 15".to_string()),
+            ],
+            found
+        );
+
+        // 81 + 40 * 27 and 81 * 40 + 27 both come to 3267.
+        assert_eq!(2, solver.count(3267, &equations[1].1));
+        assert_eq!(0, solver.count(83, &equations[2].1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_many_operands() {
+        // Twenty operators, past what a 16-bit mask could hold.
+        let ones = vec![1; 21];
+        let solver = Solver::part_1();
+        assert!(solver.solvable(21, &ones));
+        assert!(solver.solvable(1, &ones));
+        assert!(!solver.solvable(22, &ones));
+        assert_eq!(1, solver.count(21, &ones));
+    }
+
+    #[test]
+    fn test_zeros_and_overflow() {
+        // Multiplying by zero at the end makes anything before it fine, as
+        // long as every step on the way fits.
+        let solver = Solver::part_2();
+        assert!(!solver.solvable(0, &[u64::MAX, u64::MAX, 0]));
+        let found = solver.solve(0, &[u64::MAX, 1, 0]).unwrap();
+        assert_eq!(format!("{} * 1 * 0", u64::MAX), found.to_string());
+        assert_eq!(1, solver.count(0, &[u64::MAX, 1, 0]));
+        assert_eq!(3, solver.count(0, &[1, 1, 0]));
+        assert!(!solver.solvable(5, &[u64::MAX, 0]));
+        assert!(solver.solvable(7, &[0, 0, 7]));
+        assert!(!solver.solvable(u64::MAX, &[u64::MAX, 2]));
+        assert!(solver.solvable(u64::MAX, &[u64::MAX / 10, 5]));
+    }
+
+    #[test]
+    fn test_registered_operators() {
+        let solver = Solver::new().register(Sub).register(Div).register(Pow);
+        assert_eq!(
+            "2 ** 10 - 24",
+            solver.solve(1000, &[2, 10, 24]).unwrap().to_string()
+        );
+        assert_eq!(
+            "100 / 7 / 2",
+            solver.solve(7, &[100, 7, 2]).unwrap().to_string()
+        );
+        assert!(!solver.solvable(1, &[3, 5]));
+
+        let solver = Solver::part_1().register(Xor);
+        let found = solver.solve(6, &[3, 5]).unwrap();
+        assert_eq!(
+            ("3 ^ 5".to_string(), Some(6)),
+            (found.to_string(), found.value())
+        );
+    }
+
+    #[test]
+    fn test_large_divisors() {
+        // Each division could have come from a billion left operands, so they
+        // are checked by evaluating what comes before forwards instead.
+        let solver = Solver::new().register(Add).register(Div);
+        let operands = [6_000_000_000_000_000_000, 1_000_000_000, 1_000_000_000, 2];
+        let found = solver.solve(8, &operands).unwrap();
+        assert_eq!(
+            "6000000000000000000 / 1000000000 / 1000000000 + 2",
+            found.to_string()
+        );
+        assert_eq!(1, solver.count(8, &operands));
+        assert!(!solver.solvable(9, &operands));
+    }
+
+    /// Every way of filling in the operators, evaluated forwards.
+    fn brute_force(solver: &Solver, target: u64, operands: &[u64]) -> u64 {
+        fn go(solver: &Solver, value: u64, rest: &[u64], target: u64) -> u64 {
+            let Some((&next, rest)) = rest.split_first() else {
+                return u64::from(value == target);
+            };
+            solver
+                .operators()
+                .filter_map(|operator| operator.apply(value, next))
+                .map(|value| go(solver, value, rest, target))
+                .sum()
+        }
+        go(solver, operands[0], &operands[1..], target)
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut seed = 7u64;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        let solvers = [
+            Solver::part_1(),
+            Solver::part_2(),
+            Solver::new().register(Sub).register(Div).register(Mult),
+            Solver::new().register(Pow).register(Xor).register(Add),
+        ];
+        for _ in 0..500 {
+            let operands = (0..1 + next(6)).map(|_| next(12)).collect::<Vec<_>>();
+            let target = next(300);
+            for solver in &solvers {
+                let expected = brute_force(solver, target, &operands);
+                assert_eq!(
+                    expected,
+                    solver.count(target, &operands),
+                    "{}: {:?}",
+                    target,
+                    operands
+                );
+                let found = solver.solve(target, &operands);
+                assert_eq!(expected > 0, found.is_some());
+                if let Some(found) = found {
+                    assert_eq!(Some(target), found.value(), "{}", found);
+                }
+            }
+        }
+    }
+}
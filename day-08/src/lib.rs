@@ -0,0 +1,53 @@
+pub mod part_1;
+pub mod part_2;
+
+use aoc_core::{Day, Solution};
+use aoc_grid::{Coord, Grid};
+use std::collections::HashMap;
+
+pub type Frequency = char;
+pub type AntennaMap = HashMap<Frequency, Vec<Coord>>;
+pub type City = Grid<char>;
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input<'a> = City;
+    type Output = usize;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_1::process(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part_2::process(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day08>(8, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<City> {
+    Ok(Grid::parse(input, |c| match c {
+        '.' => Ok(c),
+        c if c.is_ascii_alphanumeric() => Ok(c),
+        _ => Err("an antenna or '.'"),
+    })?)
+}
+
+/// Antenna positions grouped by frequency, each group in reading order.
+pub fn antennas(city: &City) -> AntennaMap {
+    let mut antenna_map = HashMap::new();
+    for (coord, &frequency) in city.iter() {
+        if frequency != '.' {
+            antenna_map
+                .entry(frequency)
+                .or_insert_with(Vec::new)
+                .push(coord);
+        }
+    }
+    antenna_map
+}
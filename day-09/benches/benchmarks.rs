@@ -1,3 +1,4 @@
+use aoc_core::Solution;
 use day_09::*;
 
 fn main() {
@@ -6,11 +7,18 @@ fn main() {
 }
 
 #[divan::bench]
-fn bench_part1() {
-    part1::process(divan::black_box(include_str!("../input.txt",))).unwrap();
+fn bench_parse() {
+    Day09::parse(divan::black_box(include_str!("../input.txt",))).unwrap();
 }
 
 #[divan::bench]
-fn bench_part2() {
-    part2::process(divan::black_box(include_str!("../input.txt",))).unwrap();
+fn bench_part1(bencher: divan::Bencher) {
+    let input = Day09::parse(include_str!("../input.txt",)).unwrap();
+    bencher.bench(|| Day09::solve_part1(divan::black_box(&input)).unwrap());
+}
+
+#[divan::bench]
+fn bench_part2(bencher: divan::Bencher) {
+    let input = Day09::parse(include_str!("../input.txt",)).unwrap();
+    bencher.bench(|| Day09::solve_part2(divan::black_box(&input)).unwrap());
 }
@@ -1,2 +1,40 @@
 pub mod part1;
 pub mod part2;
+
+use aoc_core::{Day, ParseError, Solution};
+
+pub type DiskMap = Vec<usize>;
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input<'a> = DiskMap;
+    type Output = usize;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part1::process(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part2::process(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day09>(9, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<DiskMap> {
+    let disk_map = input
+        .trim_end()
+        .char_indices()
+        .map(|(offset, x)| {
+            x.to_digit(10)
+                .map(|count| count as usize)
+                .ok_or_else(|| ParseError::new(input, offset, "a digit"))
+        })
+        .collect::<Result<DiskMap, ParseError>>()?;
+    Ok(disk_map)
+}
@@ -1,17 +1,4 @@
-use std::error::Error;
-use std::fs;
-
-const FILE_PATH: &str = "./input.txt";
-
-pub fn run() -> Result<String, Box<dyn Error>> {
-    let contents = fs::read_to_string(FILE_PATH)?;
-    dbg!(&contents);
-
-    match process(&contents) {
-        Ok(result) => Ok(result.to_string()),
-        Err(e) => Err(e.into()),
-    }
-}
+use crate::DiskMap;
 
 type Id = usize;
 
@@ -22,18 +9,16 @@ enum Disk {
 }
 
 #[tracing::instrument]
-pub fn process(input: &str) -> Result<usize, String> {
+pub fn process(input: &DiskMap) -> usize {
     let mut disk: Vec<Disk> = input
-        .trim()
-        .chars()
+        .iter()
         .enumerate()
-        .flat_map(|(idx, x)| {
-            let count = x.to_digit(10).unwrap() as usize;
+        .flat_map(|(idx, &count)| {
             if idx % 2 == 0 {
                 let id = idx / 2;
-                return vec![Disk::FileBlock(id); count];
+                vec![Disk::FileBlock(id); count]
             } else {
-                return vec![Disk::FreeSpace; count];
+                vec![Disk::FreeSpace; count]
             }
         })
         .collect();
@@ -62,52 +47,31 @@ pub fn process(input: &str) -> Result<usize, String> {
     }
 
     // dbg!(&disk);
-    // print_disk(&disk);
 
-    let result = disk
-        .into_iter()
+    disk.into_iter()
         .enumerate()
         .fold(0, |acc, (idx, x)| match x {
-            Disk::FileBlock(id) => {
-                return acc + id * idx;
-            }
-            Disk::FreeSpace => {
-                return acc;
-            }
-        });
-
-    return Ok(result);
-}
-
-fn print_disk(disk: &Vec<Disk>) {
-    for x in disk {
-        match x {
-            Disk::FileBlock(id) => {
-                print!("{}", id);
-            }
-            Disk::FreeSpace => {
-                print!("-");
-            }
-        }
-    }
-    println!();
+            Disk::FileBlock(id) => acc + id * idx,
+            Disk::FreeSpace => acc,
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse;
 
     #[test]
-    fn test_process() -> Result<(), String> {
+    fn test_process() -> miette::Result<()> {
         let contents = "12345";
-        assert_eq!(60, process(contents)?);
+        assert_eq!(60, process(&parse(contents)?));
         Ok(())
     }
 
     #[test]
-    fn test_process_2() -> Result<(), String> {
+    fn test_process_2() -> miette::Result<()> {
         let contents = "2333133121414131402";
-        assert_eq!(1928, process(contents)?);
+        assert_eq!(1928, process(&parse(contents)?));
         Ok(())
     }
 }
@@ -0,0 +1,48 @@
+pub mod part1;
+pub mod part2;
+
+use aoc_core::{Day, Solution};
+use aoc_grid::{Coord, Grid};
+
+pub type Position = Coord;
+pub type Elevation = i32;
+pub type Topo = Grid<Elevation>;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input<'a> = Topo;
+    type Output = u32;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part1::process(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part2::process(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day10>(10, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<Topo> {
+    Ok(Grid::parse(input, parse_elevation)?)
+}
+
+pub fn find_all_positins(topo: &Topo, target: Elevation) -> Vec<Position> {
+    topo.iter()
+        .filter(|(_, &value)| value == target)
+        .map(|(position, _)| position)
+        .collect()
+}
+
+fn parse_elevation(c: char) -> Result<Elevation, &'static str> {
+    match c.to_digit(10) {
+        Some(digit) => Ok(digit as Elevation),
+        None => Err("an elevation digit"),
+    }
+}
@@ -0,0 +1,35 @@
+pub mod part1;
+pub mod part2;
+
+use aoc_core::parse::{list1, parse_all};
+use aoc_core::{Day, Solution};
+use nom::{
+    character::complete::{self, space1},
+    error::context,
+};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input<'a> = Vec<u64>;
+    type Output = u64;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part1::process(input))
+    }
+
+    fn solve_part2(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part2::process(input))
+    }
+}
+
+pub const DAY: Day = Day::new::<Day11>(11, env!("CARGO_MANIFEST_DIR"));
+
+pub fn parse(input: &str) -> miette::Result<Vec<u64>> {
+    let stones = list1(space1, context("a stone number", complete::u64));
+    Ok(parse_all(input, stones)?)
+}
@@ -1,32 +1,16 @@
-use std::error::Error;
-use std::fs;
-
-const FILE_PATH: &str = "./input.txt";
-
-pub fn run() -> Result<String, Box<dyn Error>> {
-    let contents = fs::read_to_string(FILE_PATH)?;
-    match process(&contents) {
-        Ok(result) => Ok(result.to_string()),
-        Err(e) => Err(e.into()),
-    }
-}
-
 type Stone = String;
 type Stones = Vec<Stone>;
 
-fn process(input: &str) -> Result<u32, String> {
-    let mut stones = input
-        .split_whitespace()
-        .map(|s| s.to_string())
-        .collect::<Stones>();
+pub fn process(input: &[u64]) -> u64 {
+    let mut stones = input.iter().map(|s| s.to_string()).collect::<Stones>();
 
-    for x in 0..25 {
+    for _x in 0..25 {
         // print_stones(&stones);
-        println!("{} {}", x, stones.len());
+        // println!("{} {}", x, stones.len());
         stones = blink(stones);
     }
 
-    Ok(stones.len() as u32)
+    stones.len() as u64
 }
 
 fn blink(stones: Stones) -> Stones {
@@ -41,7 +25,7 @@ fn process_stone(stone: Stone) -> Stones {
         return vec!["1".to_string()];
     }
 
-    if stone.len() % 2 == 0 {
+    if stone.len().is_multiple_of(2) {
         let left = &stone[..stone.len() / 2];
         let right = remove_leading_zeros(&stone[stone.len() / 2..]);
 
@@ -56,12 +40,13 @@ fn process_stone(stone: Stone) -> Stones {
 fn remove_leading_zeros(input: &str) -> String {
     let result = input.trim_start_matches('0').to_string();
     if result.is_empty() {
-        return "0".to_string();
+        "0".to_string()
     } else {
-        return result;
+        result
     }
 }
 
+#[allow(dead_code)]
 fn print_stones(stones: &Stones) {
     let stones = stones
         .iter()
@@ -73,11 +58,12 @@ fn print_stones(stones: &Stones) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse;
 
     #[test]
-    fn test_process() -> Result<(), String> {
+    fn test_process() -> miette::Result<()> {
         let contents = "125 17";
-        assert_eq!(55312, process(contents)?);
+        assert_eq!(55312, process(&parse(contents)?));
         Ok(())
     }
 }
@@ -1,19 +1,6 @@
 use std::collections::HashMap;
-use std::error::Error;
-use std::fs;
-
-const FILE_PATH: &str = "./input.txt";
-
-pub fn run() -> Result<String, Box<dyn Error>> {
-    let contents = fs::read_to_string(FILE_PATH)?;
-    match process(&contents) {
-        Ok(result) => Ok(result.to_string()),
-        Err(e) => Err(e.into()),
-    }
-}
 
 type Stone = u64;
-type Stones = Vec<u64>;
 enum NextStones {
     Single(Stone),
     Double(Stone, Stone),
@@ -24,12 +11,7 @@ type Value = u64;
 type StoneIteration = (Stone, Iteration);
 type CountCache = HashMap<StoneIteration, Value>;
 
-fn process(input: &str) -> Result<u64, String> {
-    let stones = input
-        .split_whitespace()
-        .map(|s| s.parse::<u64>().unwrap())
-        .collect::<Stones>();
-
+pub fn process(stones: &[Stone]) -> u64 {
     let mut count_cache: CountCache = HashMap::new();
     count_cache.insert((0, 1), 1);
     count_cache.insert((0, 0), 1);
@@ -46,7 +28,7 @@ fn process(input: &str) -> Result<u64, String> {
         acc + result
     });
 
-    Ok(result)
+    result
 }
 
 fn calculate_value(stone_iteration: StoneIteration, count_cache: &mut CountCache) -> Value {
@@ -75,7 +57,7 @@ fn calculate_value(stone_iteration: StoneIteration, count_cache: &mut CountCache
 
             count_cache.insert(stone_iteration, next_value);
 
-            return next_value;
+            next_value
         }
         NextStones::Double(left_stone, right_stone) => {
             let left_stone_iteration = (left_stone, iteration - 1);
@@ -88,7 +70,7 @@ fn calculate_value(stone_iteration: StoneIteration, count_cache: &mut CountCache
 
             count_cache.insert(stone_iteration, next_value);
 
-            return next_value;
+            next_value
         }
     }
 }
@@ -101,7 +83,7 @@ fn process_stone(stone: &Stone) -> NextStones {
     let stone_str = format!("{}", stone);
     let stone_len = stone_str.len();
     if stone_len % 2 == 0 {
-        let left = (&stone_str[..stone_len / 2]).parse::<u64>().unwrap();
+        let left = stone_str[..stone_len / 2].parse::<u64>().unwrap();
         let right = (remove_leading_zeros(&stone_str[stone_len / 2..]))
             .parse::<u64>()
             .unwrap();
@@ -110,26 +92,27 @@ fn process_stone(stone: &Stone) -> NextStones {
     }
 
     let next = stone * 2024;
-    return NextStones::Single(next);
+    NextStones::Single(next)
 }
 
 fn remove_leading_zeros(input: &str) -> &str {
     let result = input.trim_start_matches('0');
     if result.is_empty() {
-        return "0";
+        "0"
     } else {
-        return result;
+        result
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse;
 
     #[test]
-    fn test_process() -> Result<(), String> {
+    fn test_process() -> miette::Result<()> {
         let contents = "125 17";
-        assert_eq!(65601038650482, process(contents)?);
+        assert_eq!(65601038650482, process(&parse(contents)?));
         Ok(())
     }
 }
@@ -0,0 +1,26 @@
+pub mod part1;
+// pub mod part2;
+
+use aoc_core::{Day, Solution};
+use miette::miette;
+
+pub struct DayXX;
+
+impl Solution for DayXX {
+    type Input<'a> = Vec<&'a str>;
+    type Output = u32;
+
+    fn parse(input: &str) -> miette::Result<Self::Input<'_>> {
+        Ok(input.lines().collect())
+    }
+
+    fn solve_part1(input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Ok(part1::process(input))
+    }
+
+    fn solve_part2(_input: &Self::Input<'_>) -> miette::Result<Self::Output> {
+        Err(miette!("part 2 is not implemented"))
+    }
+}
+
+pub const DAY: Day = Day::new::<DayXX>(0, env!("CARGO_MANIFEST_DIR"));